@@ -1,17 +1,276 @@
-use std::collections::{HashMap, HashSet};
 use itertools::Itertools;
+use ndarray::Array2;
+use pretty_assertions::Comparison;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Write};
+use std::hash::Hash;
+
+/// Asserts both maps are equal, reporting missing keys, extra keys and differing values
+/// sorted by key instead of dumping both maps.
+#[track_caller]
+pub fn compare_maps<K, V>(actual: &HashMap<K, V>, expected: &HashMap<K, V>)
+where
+    K: Ord + Hash + Debug,
+    V: PartialEq + Debug,
+{
+    if let Some(report) = diff_maps(actual, expected) {
+        panic!("Maps are different:\n{report}");
+    }
+}
+
+#[track_caller]
+pub fn compare_sets<T>(actual: &HashSet<T>, expected: &HashSet<T>)
+where
+    T: Ord + Hash + Debug,
+{
+    if let Some(report) = diff_sets(actual, expected) {
+        panic!("Sets are different:\n{report}");
+    }
+}
+
+#[track_caller]
+pub fn compare_vecs<T>(actual: &[T], expected: &[T])
+where
+    T: PartialEq + Debug,
+{
+    if let Some(report) = diff_vecs(actual, expected) {
+        panic!("Vectors are different:\n{report}");
+    }
+}
+
+/// Asserts both grids are equal. On failure both grids are printed side by side, followed by a
+/// grid where mismatched cells are marked with `X`.
+#[track_caller]
+pub fn compare_grids<T>(actual: &Array2<T>, expected: &Array2<T>)
+where
+    T: PartialEq + Display,
+{
+    if let Some(report) = diff_grids(actual, expected) {
+        panic!("Grids are different:\n{report}");
+    }
+}
+
+fn diff_maps<K, V>(actual: &HashMap<K, V>, expected: &HashMap<K, V>) -> Option<String>
+where
+    K: Ord + Hash + Debug,
+    V: PartialEq + Debug,
+{
+    let missing = expected
+        .keys()
+        .filter(|k| !actual.contains_key(k))
+        .sorted()
+        .collect_vec();
+    let extra = actual
+        .keys()
+        .filter(|k| !expected.contains_key(k))
+        .sorted()
+        .collect_vec();
+    let different = actual
+        .iter()
+        .filter_map(|(k, v_actual)| {
+            let v_expected = expected.get(k)?;
+            if v_actual == v_expected {
+                None
+            } else {
+                Some((k, v_actual, v_expected))
+            }
+        })
+        .sorted_by(|left, right| left.0.cmp(right.0))
+        .collect_vec();
+    if missing.is_empty() && extra.is_empty() && different.is_empty() {
+        return None;
+    }
+
+    let mut report = String::new();
+    if !missing.is_empty() {
+        writeln!(report, "Missing keys:").unwrap();
+        for k in missing {
+            writeln!(report, "  {k:?} => {:?}", expected[k]).unwrap();
+        }
+    }
+    if !extra.is_empty() {
+        writeln!(report, "Extra keys:").unwrap();
+        for k in extra {
+            writeln!(report, "  {k:?} => {:?}", actual[k]).unwrap();
+        }
+    }
+    if !different.is_empty() {
+        writeln!(report, "Different values:").unwrap();
+        for (k, v_actual, v_expected) in different {
+            writeln!(report, "  {k:?}:").unwrap();
+            writeln!(report, "{}", Comparison::new(v_actual, v_expected)).unwrap();
+        }
+    }
+    Some(report)
+}
+
+fn diff_sets<T>(actual: &HashSet<T>, expected: &HashSet<T>) -> Option<String>
+where
+    T: Ord + Hash + Debug,
+{
+    let missing = expected.difference(actual).sorted().collect_vec();
+    let extra = actual.difference(expected).sorted().collect_vec();
+    if missing.is_empty() && extra.is_empty() {
+        return None;
+    }
+    let mut report = String::new();
+    if !missing.is_empty() {
+        writeln!(report, "Missing elements: {missing:?}").unwrap();
+    }
+    if !extra.is_empty() {
+        writeln!(report, "Extra elements: {extra:?}").unwrap();
+    }
+    Some(report)
+}
+
+fn diff_vecs<T>(actual: &[T], expected: &[T]) -> Option<String>
+where
+    T: PartialEq + Debug,
+{
+    if actual == expected {
+        return None;
+    }
+    let mut report = String::new();
+    if actual.len() != expected.len() {
+        writeln!(
+            report,
+            "Different lengths: actual has {}, expected has {}",
+            actual.len(),
+            expected.len()
+        )
+        .unwrap();
+    }
+    for (i, (v_actual, v_expected)) in actual.iter().zip(expected.iter()).enumerate() {
+        if v_actual != v_expected {
+            writeln!(report, "  [{i}]: {v_actual:?} != {v_expected:?}").unwrap();
+        }
+    }
+    for (i, v) in actual.iter().enumerate().skip(expected.len()) {
+        writeln!(report, "  [{i}]: extra {v:?}").unwrap();
+    }
+    for (i, v) in expected.iter().enumerate().skip(actual.len()) {
+        writeln!(report, "  [{i}]: missing {v:?}").unwrap();
+    }
+    Some(report)
+}
+
+fn diff_grids<T>(actual: &Array2<T>, expected: &Array2<T>) -> Option<String>
+where
+    T: PartialEq + Display,
+{
+    if actual.shape() != expected.shape() {
+        return Some(format!(
+            "Different shapes: actual is {:?}, expected is {:?}\n",
+            actual.shape(),
+            expected.shape()
+        ));
+    }
+    if actual == expected {
+        return None;
+    }
+
+    let width = actual
+        .iter()
+        .chain(expected.iter())
+        .map(|v| v.to_string().len())
+        .max()
+        .unwrap_or(1);
+    let render_row = |grid: &Array2<T>, row: usize| {
+        grid.row(row)
+            .iter()
+            .map(|v| format!("{:>width$}", v.to_string()))
+            .join(" ")
+    };
+    let mut report = String::new();
+    writeln!(report, "actual | expected | mismatches").unwrap();
+    for row in 0..actual.nrows() {
+        let mismatches = actual
+            .row(row)
+            .iter()
+            .zip(expected.row(row).iter())
+            .map(|(a, e)| {
+                let marker = if a == e { "." } else { "X" };
+                format!("{marker:>width$}")
+            })
+            .join(" ");
+        writeln!(
+            report,
+            "{} | {} | {}",
+            render_row(actual, row),
+            render_row(expected, row),
+            mismatches
+        )
+        .unwrap();
+    }
+    let positions = actual
+        .indexed_iter()
+        .filter(|(pos, v)| expected[*pos] != **v)
+        .map(|(pos, _)| pos)
+        .collect_vec();
+    writeln!(report, "Mismatched cells (row, column): {positions:?}").unwrap();
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use map_macro::{hash_map, hash_set};
+    use ndarray::array;
+
+    #[test]
+    fn test_diff_maps() {
+        let actual = hash_map! { 1 => "a", 2 => "b", 4 => "d" };
+        let expected = hash_map! { 1 => "a", 2 => "x", 3 => "c" };
+        let report = diff_maps(&actual, &expected).unwrap();
+        assert!(report.contains("Missing keys:\n  3 => \"c\"\n"), "{report}");
+        assert!(report.contains("Extra keys:\n  4 => \"d\"\n"), "{report}");
+        assert!(report.contains("Different values:\n  2:\n"), "{report}");
+
+        assert_eq!(diff_maps(&actual, &actual.clone()), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "Missing keys")]
+    fn test_compare_maps_panics() {
+        compare_maps(&hash_map! { 1 => 1 }, &hash_map! { 1 => 1, 2 => 2 });
+    }
+
+    #[test]
+    fn test_diff_sets() {
+        let report = diff_sets(&hash_set![1, 2, 5, 4], &hash_set![3, 2, 1]).unwrap();
+        assert_eq!(report, "Missing elements: [3]\nExtra elements: [4, 5]\n");
+        assert_eq!(diff_sets(&hash_set![1], &hash_set![1]), None);
+    }
+
+    #[test]
+    fn test_diff_vecs() {
+        let report = diff_vecs(&[1, 2, 3], &[1, 5]).unwrap();
+        assert_eq!(
+            report,
+            "Different lengths: actual has 3, expected has 2\n  [1]: 2 != 5\n  [2]: extra 3\n"
+        );
+        assert_eq!(diff_vecs(&[1, 2], &[1, 2]), None);
+    }
 
-pub fn compare_maps(actual: HashMap<(char, (usize, usize)), HashSet<u32>>, expected: HashMap<(char, (usize, usize)), HashSet<u32>>) {
-    let actual_keys = actual.keys().sorted().collect_vec();
-    let expected_keys = expected.keys().sorted().collect_vec();
-    assert_eq!(actual_keys, expected_keys);
-    for k in actual.keys().sorted() {
-        let v_actual = actual.get(k).unwrap();
-        let v_expected = expected.get(k).unwrap();
+    #[test]
+    fn test_diff_grids() {
+        let actual = array![[1, 2], [3, 40]];
+        let expected = array![[1, 2], [5, 40]];
+        let report = diff_grids(&actual, &expected).unwrap();
         assert_eq!(
-            v_actual, v_expected,
-            "Different values for key '{k:?}'.\nActual:   {v_actual:?}\nExpected: {v_expected:?}\n"
+            report,
+            [
+                "actual | expected | mismatches",
+                " 1  2 |  1  2 |  .  .",
+                " 3 40 |  5 40 |  X  .",
+                "Mismatched cells (row, column): [(1, 0)]",
+                "",
+            ]
+            .join("\n")
         );
+        assert!(diff_grids(&actual, &array![[1, 2]])
+            .unwrap()
+            .starts_with("Different shapes"));
+        assert_eq!(diff_grids(&actual, &actual.clone()), None);
     }
-    assert_eq!(actual, expected);
 }
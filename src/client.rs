@@ -0,0 +1,233 @@
+use crate::registry::input_path;
+use anyhow::{bail, ensure, Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+pub const DEFAULT_MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Local state of the client, like the time of the last request. Not committed.
+pub const STATE_DIR: &str = ".aoc";
+
+const USER_AGENT: &str = "advent-of-code solutions runner";
+
+/// Spaces requests out by at least `min_interval`, also across runs, by remembering the time of
+/// the last request in `state_file`.
+pub struct RateLimiter {
+    min_interval: Duration,
+    state_file: PathBuf,
+}
+
+impl RateLimiter {
+    pub fn new(min_interval: Duration, state_file: PathBuf) -> Self {
+        RateLimiter {
+            min_interval,
+            state_file,
+        }
+    }
+
+    fn last_request(&self) -> Option<SystemTime> {
+        let millis = fs::read_to_string(&self.state_file).ok()?;
+        let millis = millis.trim().parse::<u64>().ok()?;
+        Some(UNIX_EPOCH + Duration::from_millis(millis))
+    }
+
+    /// Sleeps until `min_interval` has passed since the last request, then records a new one.
+    pub fn wait(&self) -> Result<()> {
+        if let Some(last_request) = self.last_request() {
+            let elapsed = SystemTime::now()
+                .duration_since(last_request)
+                .unwrap_or_default();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+        let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_millis();
+        if let Some(parent) = self.state_file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&self.state_file, now.to_string())
+            .with_context(|| format!("Can't write {}", self.state_file.display()))
+    }
+}
+
+/// Talks to the Advent of Code server, or any server with the same routes at `base_url`.
+pub struct Client {
+    base_url: String,
+    session: String,
+    agent: ureq::Agent,
+    rate_limiter: RateLimiter,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: &str, rate_limiter: RateLimiter) -> Self {
+        Client {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session: session.to_string(),
+            agent: ureq::AgentBuilder::new().user_agent(USER_AGENT).build(),
+            rate_limiter,
+        }
+    }
+
+    pub fn get(&self, path: &str) -> Result<String> {
+        self.rate_limiter.wait()?;
+        let response = self
+            .agent
+            .get(&format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .call();
+        read_response(path, response)
+    }
+
+    pub fn post_form(&self, path: &str, form: &[(&str, &str)]) -> Result<String> {
+        self.rate_limiter.wait()?;
+        let response = self
+            .agent
+            .post(&format!("{}{path}", self.base_url))
+            .set("Cookie", &format!("session={}", self.session))
+            .send_form(form);
+        read_response(path, response)
+    }
+}
+
+fn read_response(path: &str, response: Result<ureq::Response, ureq::Error>) -> Result<String> {
+    match response {
+        Ok(response) => Ok(response.into_string()?),
+        Err(ureq::Error::Status(404, _)) => {
+            bail!("{path} not found, the puzzle may not be unlocked yet")
+        }
+        Err(ureq::Error::Status(400 | 401 | 403 | 500, _)) => {
+            bail!("Request to {path} was refused, the session cookie may be invalid or expired")
+        }
+        Err(ureq::Error::Status(status, response)) => bail!(
+            "Request to {path} failed with status {status}: {}",
+            response.into_string().unwrap_or_default()
+        ),
+        Err(err) => Err(err).with_context(|| format!("Request to {path} failed")),
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Fetched {
+    Cached(PathBuf),
+    Downloaded(PathBuf),
+}
+
+/// Downloads the puzzle input of a day under `root`, unless there already is a non-empty one.
+/// Empty inputs, like the ones created by `new`, are fetched.
+pub fn fetch_input(client: &Client, root: &Path, year: u32, day: u32) -> Result<Fetched> {
+    let path = root.join(input_path(year, day));
+    if fs::metadata(&path).is_ok_and(|metadata| metadata.len() > 0) {
+        return Ok(Fetched::Cached(path));
+    }
+    let input = client.get(&format!("/{year}/day/{day}/input"))?;
+    ensure!(
+        !input.is_empty(),
+        "Received an empty input for {year} day {day}"
+    );
+
+    fs::create_dir_all(path.parent().unwrap())?;
+    // Written aside first so an interrupted fetch never leaves a truncated input behind
+    let partial = path.with_extension("txt.partial");
+    fs::write(&partial, input)?;
+    fs::rename(&partial, &path)?;
+    Ok(Fetched::Downloaded(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stub_server::StubServer;
+    use std::time::Instant;
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("client-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    fn client(server: &StubServer, root: &Path) -> Client {
+        let rate_limiter = RateLimiter::new(Duration::ZERO, root.join(STATE_DIR).join("last"));
+        Client::new(&server.url, "abc123", rate_limiter)
+    }
+
+    #[test]
+    fn test_fetch_input() -> Result<()> {
+        let root = temp_root("fetch");
+        let server = StubServer::start(|_| (200, "1 2 3\n4 5 6\n".to_string()));
+        let client = client(&server, &root);
+
+        let path = root.join("inputs/2023/day4.txt");
+        assert_eq!(
+            fetch_input(&client, &root, 2023, 4)?,
+            Fetched::Downloaded(path.clone())
+        );
+        assert_eq!(fs::read_to_string(&path)?, "1 2 3\n4 5 6\n");
+        assert_eq!(
+            fetch_input(&client, &root, 2023, 4)?,
+            Fetched::Cached(path.clone())
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].method, "GET");
+        assert_eq!(requests[0].path, "/2023/day/4/input");
+        assert_eq!(requests[0].header("Cookie"), Some("session=abc123"));
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_empty_input() -> Result<()> {
+        let root = temp_root("empty");
+        let server = StubServer::start(|_| (200, "input".to_string()));
+        fs::create_dir_all(root.join("inputs/2023"))?;
+        fs::write(root.join("inputs/2023/day7.txt"), "")?;
+
+        let fetched = fetch_input(&client(&server, &root), &root, 2023, 7)?;
+        assert!(matches!(fetched, Fetched::Downloaded(_)));
+        assert_eq!(
+            fs::read_to_string(root.join("inputs/2023/day7.txt"))?,
+            "input"
+        );
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_fetch_errors() {
+        let root = temp_root("errors");
+        let server = StubServer::start(|request| match request.path.as_str() {
+            "/2023/day/25/input" => (404, "Not found".to_string()),
+            _ => (400, "Please log in".to_string()),
+        });
+        let client = client(&server, &root);
+
+        let error = fetch_input(&client, &root, 2023, 25).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "/2023/day/25/input not found, the puzzle may not be unlocked yet"
+        );
+        let error = fetch_input(&client, &root, 2023, 1).unwrap_err();
+        assert!(error.to_string().contains("session cookie"), "{error}");
+        assert!(!root.join("inputs/2023/day1.txt").exists());
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rate_limiter() -> Result<()> {
+        let root = temp_root("rate");
+        let state_file = root.join("last");
+        let start = Instant::now();
+        RateLimiter::new(Duration::from_millis(200), state_file.clone()).wait()?;
+        assert!(start.elapsed() < Duration::from_millis(200));
+        // A new limiter, like a new run, still waits for the previous request. The time of the
+        // request is stored in whole milliseconds, hence the margin.
+        RateLimiter::new(Duration::from_millis(200), state_file).wait()?;
+        assert!(start.elapsed() >= Duration::from_millis(195));
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -1,43 +1,230 @@
+use itertools::Itertools;
+use std::fmt::{Display, Formatter};
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum GetManyMutError {
+    IndexOutOfBounds { index: usize, len: usize },
+    DuplicateIndex(usize),
+}
+
+impl Display for GetManyMutError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GetManyMutError::IndexOutOfBounds { index, len } => {
+                write!(f, "Index {index} is out of bounds for a slice of length {len}")
+            }
+            GetManyMutError::DuplicateIndex(index) => write!(
+                f,
+                "Index {index} was requested more than once, can't return 2 mutable references to the same element"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for GetManyMutError {}
+
+/// Returns mutable references to the elements at `indices`, in the same order as `indices`.
+pub fn get_many_mut<T, const N: usize>(
+    v: &mut [T],
+    indices: [usize; N],
+) -> Result<[&mut T; N], GetManyMutError> {
+    let len = v.len();
+    let sorted = indices
+        .iter()
+        .copied()
+        .enumerate()
+        .sorted_by_key(|(_, index)| *index)
+        .collect_vec();
+    for (_, index) in sorted.iter() {
+        if *index >= len {
+            return Err(GetManyMutError::IndexOutOfBounds { index: *index, len });
+        }
+    }
+    for ((_, previous), (_, current)) in sorted.iter().tuple_windows() {
+        if previous == current {
+            return Err(GetManyMutError::DuplicateIndex(*current));
+        }
+    }
+
+    let mut slots: [Option<&mut T>; N] = std::array::from_fn(|_| None);
+    let mut rest = v;
+    let mut offset = 0;
+    for (position, index) in sorted {
+        let (_, tail) = std::mem::take(&mut rest).split_at_mut(index - offset);
+        let (elem, tail) = tail.split_first_mut().expect("Bounds were checked above");
+        slots[position] = Some(elem);
+        rest = tail;
+        offset = index + 1;
+    }
+    Ok(slots.map(|slot| slot.expect("Every index was filled above")))
+}
+
 pub fn get_mut2<T>(v: &mut [T], i: usize, j: usize) -> (&mut T, &mut T) {
-    if i == j {
-        panic!("Can't return 2 mutable references to the same element");
+    match get_many_mut(v, [i, j]) {
+        Ok([left, right]) => (left, right),
+        Err(err) => panic!("{err}"),
     }
-    let (start, end) = if i < j { (i, j) } else { (j, i) };
+}
+
+/// Walks over the pairs `(k, k + 1)` of a slice, handing out both elements mutably.
+///
+/// This can't be an `Iterator` because consecutive pairs share an element, so each pair must be
+/// dropped before asking for the next one:
+///
+/// ```ignore
+/// let mut pairs = pairs_mut(&mut knots);
+/// while let Some((head, tail)) = pairs.next_pair() { ... }
+/// ```
+pub struct PairsMut<'a, T> {
+    slice: &'a mut [T],
+    index: usize,
+}
 
-    let (first, second) = v.split_at_mut(start + 1);
-    let left = &mut first[start];
-    let right = &mut second[end - start - 1];
+pub fn pairs_mut<T>(slice: &mut [T]) -> PairsMut<'_, T> {
+    PairsMut { slice, index: 0 }
+}
 
-    if i < j {
-        (left, right)
-    } else {
-        (right, left)
+impl<'a, T> PairsMut<'a, T> {
+    pub fn next_pair(&mut self) -> Option<(&mut T, &mut T)> {
+        if self.index + 1 >= self.slice.len() {
+            return None;
+        }
+        let (left, right) = self.slice.split_at_mut(self.index + 1);
+        self.index += 1;
+        Some((&mut left[self.index - 1], &mut right[0]))
     }
 }
 
-pub fn split_into_slices<'a, T, const N: usize>(
+/// Calls `f` on every pair `(k, k + 1)`, in order.
+pub fn for_each_pair_mut<T, F>(slice: &mut [T], mut f: F)
+where
+    F: FnMut(&mut T, &mut T),
+{
+    let mut pairs = pairs_mut(slice);
+    while let Some((left, right)) = pairs.next_pair() {
+        f(left, right);
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum SplitError {
+    IndexOutOfBounds { index: usize, len: usize },
+    UnsortedIndices { previous: usize, index: usize },
+    WrongNumberOfGroups { expected: usize, found: usize },
+    NotDivisible { len: usize, group_size: usize },
+}
+
+impl Display for SplitError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SplitError::IndexOutOfBounds { index, len } => write!(
+                f,
+                "Tried to split at index {index}, but slice has length {len}"
+            ),
+            SplitError::UnsortedIndices { previous, index } => write!(
+                f,
+                "Split indices must be in ascending order, but {index} comes after {previous}"
+            ),
+            SplitError::WrongNumberOfGroups { expected, found } => {
+                write!(f, "Expected {expected} groups, but found {found}")
+            }
+            SplitError::NotDivisible { len, group_size } => {
+                write!(f, "Can't split {len} elements into groups of {group_size}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for SplitError {}
+
+/// Splits `slice` at each of `indices`, which must be in ascending order. Returns the `N` slices
+/// ending at each index plus whatever comes after the last index.
+pub fn split_at_indices<'a, T, const N: usize>(
     slice: &'a [T],
     indices: &[usize; N],
-) -> Result<[&'a [T]; N], String> {
-    let max_index = *indices.iter().max().ok_or("Empty slice".to_string())?;
-    if max_index > slice.len() {
-        return Err(format!(
-            "Tried to split at index {max_index}, but slice has length {}",
-            slice.len()
-        ));
-    }
-    let ret = std::array::from_fn::<&[T], N, _>(|i| {
+) -> Result<([&'a [T]; N], &'a [T]), SplitError> {
+    let mut previous = 0;
+    for index in indices.iter().copied() {
+        if index > slice.len() {
+            return Err(SplitError::IndexOutOfBounds {
+                index,
+                len: slice.len(),
+            });
+        }
+        if index < previous {
+            return Err(SplitError::UnsortedIndices { previous, index });
+        }
+        previous = index;
+    }
+    let slices = std::array::from_fn::<&[T], N, _>(|i| {
         let index = indices[i];
         let previous_index = if i > 0 { indices[i - 1] } else { 0 };
         &slice[previous_index..index]
     });
+    Ok((slices, &slice[previous..]))
+}
+
+/// Same as [split_at_indices], but drops the remainder.
+pub fn split_into_slices<'a, T, const N: usize>(
+    slice: &'a [T],
+    indices: &[usize; N],
+) -> Result<[&'a [T]; N], SplitError> {
+    split_at_indices(slice, indices).map(|(slices, _)| slices)
+}
+
+/// Splits `slice` on every element matching `is_separator`, dropping the separators. Consecutive
+/// separators don't produce empty groups, so blank lines can be used to split sections.
+pub fn split_by<T, F>(slice: &[T], is_separator: F) -> Vec<&[T]>
+where
+    F: Fn(&T) -> bool,
+{
+    slice
+        .split(is_separator)
+        .filter(|group| !group.is_empty())
+        .collect_vec()
+}
+
+/// Same as [split_by], but fails unless there are exactly `N` groups.
+pub fn split_by_exactly<T, F, const N: usize>(
+    slice: &[T],
+    is_separator: F,
+) -> Result<[&[T]; N], SplitError>
+where
+    F: Fn(&T) -> bool,
+{
+    let groups = split_by(slice, is_separator);
+    let found = groups.len();
+    groups
+        .try_into()
+        .map_err(|_| SplitError::WrongNumberOfGroups { expected: N, found })
+}
+
+/// Splits `slice` into consecutive groups of exactly `N` elements.
+pub fn chunk_into_groups<T, const N: usize>(slice: &[T]) -> Result<Vec<&[T; N]>, SplitError> {
+    if N == 0 || !slice.len().is_multiple_of(N) {
+        return Err(SplitError::NotDivisible {
+            len: slice.len(),
+            group_size: N,
+        });
+    }
+    let ret = slice
+        .chunks_exact(N)
+        .map(|chunk| {
+            chunk
+                .try_into()
+                .expect("chunks_exact returns chunks of N elements")
+        })
+        .collect_vec();
     Ok(ret)
 }
 
 #[cfg(test)]
 mod tests {
     // use pretty_assertions::{assert_eq, assert_ne};
-    use crate::containers::{get_mut2, split_into_slices};
+    use crate::containers::{
+        chunk_into_groups, for_each_pair_mut, get_many_mut, get_mut2, pairs_mut, split_at_indices,
+        split_by, split_by_exactly, split_into_slices, GetManyMutError, SplitError,
+    };
 
     #[test]
     fn test_get_mut2() -> Result<(), String> {
@@ -53,7 +240,68 @@ mod tests {
     }
 
     #[test]
-    fn test_split_into_slices() -> Result<(), String> {
+    #[should_panic]
+    fn test_get_mut2_same_index() {
+        let mut source = vec![1, 2, 3];
+        get_mut2(source.as_mut_slice(), 1, 1);
+    }
+
+    #[test]
+    fn test_get_many_mut() -> Result<(), GetManyMutError> {
+        let mut source = vec![1, 2, 3, 4, 5];
+        let [a, b, c] = get_many_mut(&mut source, [4, 0, 2])?;
+        assert_eq!((*a, *b, *c), (5, 1, 3));
+        *a = 50;
+        *b = 10;
+        *c = 30;
+        assert_eq!(source, vec![10, 2, 30, 4, 50]);
+
+        let [] = get_many_mut::<i32, 0>(&mut source, [])?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_many_mut_errors() {
+        let mut source = vec![1, 2, 3];
+        assert_eq!(
+            get_many_mut(&mut source, [0, 3]).unwrap_err(),
+            GetManyMutError::IndexOutOfBounds { index: 3, len: 3 }
+        );
+        assert_eq!(
+            get_many_mut(&mut source, [2, 0, 2]).unwrap_err(),
+            GetManyMutError::DuplicateIndex(2)
+        );
+    }
+
+    #[test]
+    fn test_pairs_mut() {
+        let mut source = vec![1, 2, 3, 4];
+        let mut pairs = pairs_mut(&mut source);
+        let mut visited = vec![];
+        while let Some((left, right)) = pairs.next_pair() {
+            visited.push((*left, *right));
+            *right += *left;
+        }
+        assert_eq!(visited, vec![(1, 2), (3, 3), (6, 4)]);
+        assert_eq!(source, vec![1, 3, 6, 10]);
+
+        let mut single = vec![1];
+        assert!(pairs_mut(&mut single).next_pair().is_none());
+    }
+
+    #[test]
+    fn test_for_each_pair_mut() {
+        let mut source = vec![5, 1, 4];
+        for_each_pair_mut(&mut source, |left, right| {
+            if left > right {
+                std::mem::swap(left, right);
+            }
+        });
+        assert_eq!(source, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn test_split_into_slices() -> Result<(), SplitError> {
         let v = vec!["a", "b", "c", "d"];
         let ret = split_into_slices(&v, &[1, 2, 3])?;
         assert_eq!(ret, [
@@ -65,7 +313,7 @@ mod tests {
     }
 
     #[test]
-    fn test_split_into_slices_max_index() -> Result<(), String> {
+    fn test_split_into_slices_max_index() -> Result<(), SplitError> {
         let v = vec!["a", "b", "c", "d"];
         let ret = split_into_slices(&v, &[1, 4])?;
         assert_eq!(ret, [
@@ -81,4 +329,75 @@ mod tests {
         let v = vec!["a", "b", "c", "d"];
         split_into_slices(&v, &[20]).unwrap();
     }
+
+    #[test]
+    fn test_split_at_indices() -> Result<(), SplitError> {
+        let v = vec!["a", "b", "c", "d", "e"];
+        let (slices, rest) = split_at_indices(&v, &[1, 3])?;
+        assert_eq!(slices, [&v[0..1], &v[1..3]]);
+        assert_eq!(rest, &v[3..]);
+
+        let (slices, rest) = split_at_indices(&v, &[2, 2, 5])?;
+        assert_eq!(slices, [&v[0..2], &v[2..2], &v[2..5]]);
+        assert!(rest.is_empty());
+
+        let (slices, rest) = split_at_indices(&v, &[])?;
+        assert_eq!(slices.len(), 0);
+        assert_eq!(rest, &v[..]);
+        Ok(())
+    }
+
+    #[test]
+    fn test_split_at_indices_errors() {
+        let v = vec!["a", "b", "c", "d"];
+        assert_eq!(
+            split_at_indices(&v, &[3, 1]).unwrap_err(),
+            SplitError::UnsortedIndices {
+                previous: 3,
+                index: 1
+            }
+        );
+        assert_eq!(
+            split_at_indices(&v, &[1, 20]).unwrap_err(),
+            SplitError::IndexOutOfBounds { index: 20, len: 4 }
+        );
+        assert_eq!(
+            split_at_indices(&v, &[1, 20]).unwrap_err().to_string(),
+            "Tried to split at index 20, but slice has length 4"
+        );
+    }
+
+    #[test]
+    fn test_split_by() -> Result<(), SplitError> {
+        let lines = vec!["", "[D]", "1 2", "", "", "move 1", "move 2", ""];
+        let groups = split_by(&lines, |line| line.is_empty());
+        assert_eq!(groups, vec![&lines[1..3], &lines[5..7]]);
+
+        let [crane, movements] = split_by_exactly(&lines, |line| line.is_empty())?;
+        assert_eq!(crane, &["[D]", "1 2"]);
+        assert_eq!(movements, &["move 1", "move 2"]);
+
+        assert_eq!(
+            split_by_exactly::<_, _, 3>(&lines, |line| line.is_empty()).unwrap_err(),
+            SplitError::WrongNumberOfGroups {
+                expected: 3,
+                found: 2
+            }
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunk_into_groups() -> Result<(), SplitError> {
+        let v = vec![1, 2, 3, 4, 5, 6];
+        assert_eq!(chunk_into_groups::<_, 3>(&v)?, vec![&[1, 2, 3], &[4, 5, 6]]);
+        assert_eq!(
+            chunk_into_groups::<_, 4>(&v).unwrap_err(),
+            SplitError::NotDivisible {
+                len: 6,
+                group_size: 4
+            }
+        );
+        Ok(())
+    }
 }
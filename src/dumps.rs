@@ -0,0 +1,20 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+
+/// Directory given to `run --dump-dir`, if any.
+static DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Makes the days write their debugging dumps, like the frames of a simulation, in `dir`.
+/// Without it, days don't write anything.
+pub fn init(dir: PathBuf) -> Result<()> {
+    fs::create_dir_all(&dir).with_context(|| format!("Can't create {}", dir.display()))?;
+    DIR.set(dir)
+        .map_err(|dir| anyhow::anyhow!("Dumps already go to {}", dir.display()))
+}
+
+/// Where a day writes its dump `name`, like `2022-day9-frames.txt`, when dumps were asked for.
+pub fn path(name: &str) -> Option<PathBuf> {
+    DIR.get().map(|dir| dir.join(name))
+}
@@ -0,0 +1,226 @@
+use crate::files::puzzle_input;
+use crate::registry::Answers;
+#[cfg(test)]
+use crate::registry::Solution;
+use anyhow::{bail, Context, Result};
+use itertools::Itertools;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// An example from a puzzle description, with the answers the description gives for it.
+///
+/// Examples live next to the puzzle input: `inputs/2023/day4.example.txt` holds the example and
+/// `inputs/2023/day4.example.answers.txt` its answers. Days with more than one example number
+/// the others from 2, e.g. `day1.example2.txt`.
+#[derive(Debug)]
+pub struct Example {
+    pub path: PathBuf,
+    pub input: String,
+    pub part1: Option<String>,
+    pub part2: Option<String>,
+}
+
+impl Example {
+    /// Describes every part where `answers` differ from the expected ones. Parts without an
+    /// expected answer are not checked.
+    pub fn mismatches(&self, answers: &Answers) -> Vec<String> {
+        [
+            ("part1", &self.part1, &answers.0),
+            ("part2", &self.part2, &answers.1),
+        ]
+        .into_iter()
+        .filter_map(|(part, expected, actual)| match expected {
+            Some(expected) if expected != actual => {
+                Some(format!("{part}: expected {expected}, got {actual}"))
+            }
+            _ => None,
+        })
+        .collect_vec()
+    }
+}
+
+fn example_stem(year: u32, day: u32, index: usize) -> String {
+    match index {
+        1 => format!("inputs/{year}/day{day}.example"),
+        _ => format!("inputs/{year}/day{day}.example{index}"),
+    }
+}
+
+/// All examples of a day, in order. Stops at the first missing example file.
+pub fn examples(year: u32, day: u32) -> Result<Vec<Example>> {
+    let mut ret = vec![];
+    for index in 1.. {
+        let stem = example_stem(year, day, index);
+        let path = PathBuf::from(format!("{stem}.txt"));
+        if !path.exists() {
+            break;
+        }
+        let input = puzzle_input(&path.to_string_lossy());
+        let answers_path = PathBuf::from(format!("{stem}.answers.txt"));
+        let [part1, part2] = read_answers(&answers_path)
+            .with_context(|| format!("Invalid answers {}", answers_path.display()))?;
+        ret.push(Example {
+            path,
+            input,
+            part1,
+            part2,
+        });
+    }
+    Ok(ret)
+}
+
+fn read_answers(path: &Path) -> Result<[Option<String>; 2]> {
+    parse_answers(&fs::read_to_string(path)?)
+}
+
+/// Parses `part1: <answer>` and `part2: <answer>` lines. An answer spanning several lines starts
+/// on the line after a bare `partN:` header and runs until the next header.
+fn parse_answers(text: &str) -> Result<[Option<String>; 2]> {
+    let mut answers: [Option<Vec<&str>>; 2] = Default::default();
+    let mut multiline = None;
+    for (i, line) in text.lines().enumerate() {
+        let header = line
+            .split_once(':')
+            .and_then(|(name, value)| match name.trim() {
+                "part1" => Some((0, value.trim())),
+                "part2" => Some((1, value.trim())),
+                _ => None,
+            });
+        match (header, multiline) {
+            (Some((part, "")), _) => {
+                answers[part] = Some(vec![]);
+                multiline = Some(part);
+            }
+            (Some((part, value)), _) => {
+                answers[part] = Some(vec![value]);
+                multiline = None;
+            }
+            (None, Some(part)) => answers[part].get_or_insert_with(Vec::new).push(line),
+            (None, None) if line.trim().is_empty() => (),
+            (None, None) => bail!(
+                "Line {}: expected 'part1: <answer>' or 'part2: <answer>', found '{line}'",
+                i + 1
+            ),
+        }
+    }
+    Ok(answers.map(|lines| lines.map(|lines| lines.join("\n").trim_end().to_string())))
+}
+
+/// Input of the first example of a day, for tests of the day's helpers.
+#[cfg(test)]
+pub fn example_input(year: u32, day: u32) -> String {
+    puzzle_input(&format!("{}.txt", example_stem(year, day, 1)))
+}
+
+/// Solves every example of a day, panicking on the first one whose answers differ from the
+/// expected ones.
+#[cfg(test)]
+#[track_caller]
+pub fn assert_examples(solution: Solution) {
+    let Solution { year, day, .. } = solution;
+    let examples = examples(year, day).unwrap_or_else(|err| panic!("{err:#}"));
+    assert!(!examples.is_empty(), "No examples for {year} day {day}");
+    for example in examples {
+        let path = example.path.display();
+        let answers = solution
+            .solve_input(&example.input)
+            .unwrap_or_else(|err| panic!("{path}: {err:#}"));
+        let mismatches = example.mismatches(&answers);
+        assert!(mismatches.is_empty(), "{path}:\n{}", mismatches.join("\n"));
+    }
+}
+
+/// Generates a test checking the day's `solve` against all of its examples. Unused when no year
+/// is enabled. With `ignore = "reason"`, the test is ignored, for days not solved yet.
+#[cfg(test)]
+#[allow(unused_macros)]
+macro_rules! example_tests {
+    ($year:literal, $day:literal) => {
+        #[test]
+        fn test_example_answers() {
+            $crate::examples::assert_examples($crate::registry::Solution::new(
+                $year,
+                $day,
+                super::solve,
+            ));
+        }
+    };
+    ($year:literal, $day:literal, ignore = $reason:literal) => {
+        #[test]
+        #[ignore = $reason]
+        fn test_example_answers() {
+            $crate::examples::assert_examples($crate::registry::Solution::new(
+                $year,
+                $day,
+                super::solve,
+            ));
+        }
+    };
+}
+
+#[cfg(test)]
+#[allow(unused_imports)]
+pub(crate) use example_tests;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings::dedent;
+
+    #[test]
+    fn test_parse_answers() -> Result<()> {
+        assert_eq!(
+            parse_answers("part1: 13\npart2: 30\n")?,
+            [Some("13".to_string()), Some("30".to_string())]
+        );
+        assert_eq!(
+            parse_answers("\npart2: abc\n")?,
+            [None, Some("abc".to_string())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_multiline_answers() -> Result<()> {
+        let text = dedent(
+            "
+            part1: 1
+            part2:
+            #..#
+            .##.
+
+            ",
+        );
+        assert_eq!(
+            parse_answers(&text)?,
+            [Some("1".to_string()), Some("#..#\n.##.".to_string())]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_answers_errors() {
+        let error = parse_answers("part1: 1\npart3: 2").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Line 2: expected 'part1: <answer>' or 'part2: <answer>', found 'part3: 2'"
+        );
+    }
+
+    #[test]
+    fn test_mismatches() {
+        let example = Example {
+            path: PathBuf::from("day1.example.txt"),
+            input: String::new(),
+            part1: Some("1".to_string()),
+            part2: None,
+        };
+        assert!(example
+            .mismatches(&("1".to_string(), "2".to_string()))
+            .is_empty());
+        assert_eq!(
+            example.mismatches(&("3".to_string(), "2".to_string())),
+            vec!["part1: expected 1, got 3"]
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use itertools::Itertools;
+use crate::strings::to_lines;
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
@@ -7,12 +7,14 @@ use std::path::Path;
 pub fn contents(filename: &str) -> String {
     fs::read_to_string(filename).expect(format!("File does not exist: {filename}").as_str())
 }
+/// Contents of a puzzle input or example without its trailing newlines, which the solutions
+/// don't expect.
+pub fn puzzle_input(filename: &str) -> String {
+    contents(filename).trim_end_matches('\n').to_string()
+}
+
 pub fn lines(filename: &str) -> Vec<String> {
-    let contents =
-        fs::read_to_string(filename).expect(format!("File does not exist: {filename}").as_str());
-    let lines = contents.split("\n");
-    let vec = lines.map(|s| String::from(s)).collect_vec();
-    return vec;
+    to_lines(&contents(filename))
 }
 
 pub fn open_for_append(filename: &str) -> File {
@@ -1,11 +1,660 @@
-pub fn contains(left: (i32, i32), right: (i32, i32)) -> bool {
-    let (x0, y0) = left;
-    let (x1, y1) = right;
-    x0 <= x1 && y0 >= y1
+use itertools::Itertools;
+use num::PrimInt;
+use std::fmt::{Display, Formatter};
+use std::ops;
+
+/// Half-open interval `[start, end)`. An interval with `start >= end` is empty.
+#[derive(Eq, Hash, Ord, PartialOrd, PartialEq, Debug, Copy, Clone)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval { start, end }
+    }
+
+    /// Interval containing both `first` and `last`, like the `2-4` assignments of 2022 day4.
+    pub fn closed(first: T, last: T) -> Interval<T> {
+        Interval::new(first, last + T::one())
+    }
+
+    pub fn with_len(start: T, len: T) -> Interval<T> {
+        Interval::new(start, start + len)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start >= self.end
+    }
+
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    /// The last value inside the interval, if any.
+    pub fn last(&self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.end - T::one())
+        }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    /// Whether every value of `other` is also inside `self`. The empty interval is contained by anything.
+    pub fn contains_interval(&self, other: &Interval<T>) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Interval<T>) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        let ret = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        if ret.is_empty() {
+            None
+        } else {
+            Some(ret)
+        }
+    }
+
+    /// Smallest interval covering both, only if they overlap or touch (otherwise the result would
+    /// contain values that are in neither of them).
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if self.is_empty() {
+            return Some(*other);
+        }
+        if other.is_empty() {
+            return Some(*self);
+        }
+        if self.start > other.end || other.start > self.end {
+            return None;
+        }
+        Some(Interval::new(
+            self.start.min(other.start),
+            self.end.max(other.end),
+        ))
+    }
+
+    /// Values of `self` that are not in `other`. Returns up to two non-empty pieces, in order.
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if self.is_empty() {
+            return vec![];
+        }
+        let Some(common) = self.intersection(other) else {
+            return vec![*self];
+        };
+        [
+            Interval::new(self.start, common.start),
+            Interval::new(common.end, self.end),
+        ]
+        .into_iter()
+        .filter(|piece| !piece.is_empty())
+        .collect_vec()
+    }
+
+    /// Splits into `[start, at)` and `[at, end)`. Halves that would be empty are `None`.
+    pub fn split_at(&self, at: T) -> (Option<Interval<T>>, Option<Interval<T>>) {
+        let non_empty = |interval: Interval<T>| {
+            if interval.is_empty() {
+                None
+            } else {
+                Some(interval)
+            }
+        };
+        let at = at.max(self.start).min(self.end.max(self.start));
+        (
+            non_empty(Interval::new(self.start, at)),
+            non_empty(Interval::new(at, self.end)),
+        )
+    }
+
+    /// Moves the interval so it starts at `new_start`, keeping its length.
+    pub fn shifted_to(&self, new_start: T) -> Interval<T> {
+        Interval::with_len(new_start, self.len())
+    }
+}
+
+impl<T: Display> Display for Interval<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[{}, {})", self.start, self.end)
+    }
+}
+
+/// A set of values stored as sorted, disjoint and non-touching intervals.
+#[derive(Eq, PartialEq, Debug, Clone, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet { intervals: vec![] }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Interval<T>> {
+        self.intervals.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// Number of values in the set (not the number of intervals).
+    pub fn count(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |acc, interval| acc + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|interval| interval.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().and_then(|interval| interval.last())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.intervals
+            .binary_search_by(|interval| {
+                if interval.end <= value {
+                    std::cmp::Ordering::Less
+                } else if interval.start > value {
+                    std::cmp::Ordering::Greater
+                } else {
+                    std::cmp::Ordering::Equal
+                }
+            })
+            .is_ok()
+    }
+
+    /// Adds `interval`, merging it with every interval it overlaps or touches.
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        let mut merged = interval;
+        let mut ret = Vec::with_capacity(self.intervals.len() + 1);
+        let mut inserted = false;
+        for current in self.intervals.drain(..) {
+            if let Some(union) = merged.union(&current) {
+                merged = union;
+            } else if current.end < merged.start {
+                ret.push(current);
+            } else {
+                if !inserted {
+                    ret.push(merged);
+                    inserted = true;
+                }
+                ret.push(current);
+            }
+        }
+        if !inserted {
+            ret.push(merged);
+        }
+        self.intervals = ret;
+    }
+
+    /// Removes every value of `interval` from the set.
+    pub fn subtract(&mut self, interval: &Interval<T>) {
+        self.intervals = self
+            .intervals
+            .iter()
+            .flat_map(|current| current.difference(interval))
+            .collect_vec();
+    }
+
+    pub fn merge(&mut self, other: &IntervalSet<T>) {
+        for interval in other.iter() {
+            self.insert(*interval);
+        }
+    }
+
+    pub fn union(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ret = self.clone();
+        ret.merge(other);
+        ret
+    }
+
+    pub fn difference(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut ret = self.clone();
+        for interval in other.iter() {
+            ret.subtract(interval);
+        }
+        ret
+    }
+
+    pub fn intersection(&self, other: &IntervalSet<T>) -> IntervalSet<T> {
+        self.intervals
+            .iter()
+            .cartesian_product(other.intervals.iter())
+            .filter_map(|(left, right)| left.intersection(right))
+            .collect()
+    }
+
+    /// Values of the set that fall inside `interval`.
+    pub fn intersection_with(&self, interval: &Interval<T>) -> IntervalSet<T> {
+        self.intervals
+            .iter()
+            .filter_map(|current| current.intersection(interval))
+            .collect()
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut ret = IntervalSet::new();
+        for interval in iter {
+            ret.insert(interval);
+        }
+        ret
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        IntervalSet::from_iter([interval])
+    }
+}
+
+/// A point on the integer grid. `x` grows to the right and `y` grows downwards, like the puzzle inputs.
+#[derive(Eq, Hash, Ord, PartialOrd, PartialEq, Debug, Copy, Clone, Default)]
+pub struct Position(pub i64, pub i64);
+
+impl Position {
+    pub fn manhattan_distance(&self, other: &Position) -> u64 {
+        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
+    }
+
+    /// Whether both are the same point or neighbours, diagonals included.
+    pub fn touching(&self, other: &Position) -> bool {
+        self.0.abs_diff(other.0) <= 1 && self.1.abs_diff(other.1) <= 1
+    }
+
+    /// Sign of each coordinate: a single step in the direction of the position.
+    pub fn signum(&self) -> Position {
+        Position(self.0.signum(), self.1.signum())
+    }
 }
 
-pub fn overlaps(left: (i32, i32), right: (i32, i32)) -> bool {
-    let (a0, a1) = left;
-    let (b0, b1) = right;
-    a1 >= b0 && a0 <= b1
+impl ops::Add<Position> for Position {
+    type Output = Position;
+
+    fn add(self, rhs: Position) -> Position {
+        Position(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl ops::AddAssign<Position> for Position {
+    fn add_assign(&mut self, rhs: Position) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+    }
+}
+
+impl ops::Sub<Position> for Position {
+    type Output = Position;
+
+    fn sub(self, rhs: Position) -> Position {
+        Position(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+
+#[derive(Eq, Hash, PartialEq, Debug, Copy, Clone)]
+pub enum Direction {
+    Right,
+    Left,
+    Up,
+    Down,
+    UpRight,
+    UpLeft,
+    DownRight,
+    DownLeft,
+}
+
+impl Direction {
+    /// Offset of a single step, `Up` being towards smaller `y`.
+    pub fn offset(&self) -> Position {
+        match self {
+            Direction::Right => Position(1, 0),
+            Direction::Left => Position(-1, 0),
+            Direction::Up => Position(0, -1),
+            Direction::Down => Position(0, 1),
+            Direction::UpRight => Position(1, -1),
+            Direction::UpLeft => Position(-1, -1),
+            Direction::DownRight => Position(1, 1),
+            Direction::DownLeft => Position(-1, 1),
+        }
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum PointLocation {
+    Inside,
+    Boundary,
+    Outside,
+}
+
+/// Simple polygon on the integer grid. The last vertex connects back to the first one.
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Polygon {
+    pub vertices: Vec<Position>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Position>) -> Polygon {
+        Polygon { vertices }
+    }
+
+    /// Follows the moves from `start`, adding a vertex at the end of each one. Moves don't need to
+    /// return to `start`: the polygon is closed implicitly.
+    pub fn from_moves<I>(start: Position, moves: I) -> Polygon
+    where
+        I: IntoIterator<Item = (Direction, i64)>,
+    {
+        let mut vertices = vec![start];
+        let mut current = start;
+        for (direction, size) in moves {
+            let offset = direction.offset();
+            current += Position(offset.0 * size, offset.1 * size);
+            vertices.push(current);
+        }
+        if vertices.len() > 1 && vertices.last() == vertices.first() {
+            vertices.pop();
+        }
+        Polygon { vertices }
+    }
+
+    pub fn edges(&self) -> impl Iterator<Item = (Position, Position)> + '_ {
+        self.vertices
+            .iter()
+            .copied()
+            .circular_tuple_windows::<(Position, Position)>()
+    }
+
+    /// Twice the enclosed area (shoelace formula), which is always an integer on the grid.
+    pub fn double_area(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| a.0 * b.1 - b.0 * a.1)
+            .sum::<i64>()
+            .abs()
+    }
+
+    pub fn area(&self) -> f64 {
+        self.double_area() as f64 / 2.
+    }
+
+    /// Number of grid points lying on the edges.
+    pub fn boundary_points(&self) -> i64 {
+        self.edges()
+            .map(|(a, b)| num::integer::gcd(b.0 - a.0, b.1 - a.1))
+            .sum()
+    }
+
+    /// Number of grid points strictly inside, using Pick's theorem: `A = I + B/2 - 1`.
+    pub fn interior_points(&self) -> i64 {
+        (self.double_area() - self.boundary_points() + 2) / 2
+    }
+
+    /// Grid points inside or on the boundary, e.g. the cubes dug out by a trench and its interior.
+    pub fn lattice_points(&self) -> i64 {
+        self.interior_points() + self.boundary_points()
+    }
+
+    pub fn locate(&self, point: &Position) -> PointLocation {
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let cross = (b.0 - a.0) * (point.1 - a.1) - (point.0 - a.0) * (b.1 - a.1);
+            let within_box = a.0.min(b.0) <= point.0
+                && point.0 <= a.0.max(b.0)
+                && a.1.min(b.1) <= point.1
+                && point.1 <= a.1.max(b.1);
+            if cross == 0 && within_box {
+                return PointLocation::Boundary;
+            }
+            // Ray casting towards +x, counting edges that cross the horizontal line through `point`
+            if (a.1 > point.1) != (b.1 > point.1) {
+                let crosses_right_of_point = if b.1 > a.1 { cross > 0 } else { cross < 0 };
+                if crosses_right_of_point {
+                    inside = !inside;
+                }
+            }
+        }
+        if inside {
+            PointLocation::Inside
+        } else {
+            PointLocation::Outside
+        }
+    }
+
+    /// Whether `point` is inside or on the boundary.
+    pub fn contains(&self, point: &Position) -> bool {
+        self.locate(point) != PointLocation::Outside
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interval_basics() {
+        let interval = Interval::closed(2, 4);
+        assert_eq!(interval, Interval::new(2, 5));
+        assert_eq!(interval.len(), 3);
+        assert_eq!(interval.last(), Some(4));
+        assert!(interval.contains(2));
+        assert!(interval.contains(4));
+        assert!(!interval.contains(5));
+        assert!(Interval::new(3, 3).is_empty());
+        assert_eq!(Interval::new(5, 3).len(), 0);
+        assert_eq!(Interval::with_len(98, 2), Interval::new(98, 100));
+    }
+
+    #[test]
+    fn test_interval_contains_and_overlaps() {
+        assert!(Interval::closed(2, 8).contains_interval(&Interval::closed(3, 7)));
+        assert!(Interval::closed(4, 6).contains_interval(&Interval::closed(6, 6)));
+        assert!(!Interval::closed(2, 6).contains_interval(&Interval::closed(4, 8)));
+
+        assert!(Interval::closed(5, 7).overlaps(&Interval::closed(7, 9)));
+        assert!(!Interval::closed(2, 3).overlaps(&Interval::closed(4, 5)));
+        assert!(!Interval::closed(2, 4).overlaps(&Interval::closed(6, 8)));
+    }
+
+    #[test]
+    fn test_interval_intersection_union() {
+        let a = Interval::new(0, 10);
+        let b = Interval::new(5, 15);
+        assert_eq!(a.intersection(&b), Some(Interval::new(5, 10)));
+        assert_eq!(a.intersection(&Interval::new(10, 12)), None);
+
+        assert_eq!(a.union(&b), Some(Interval::new(0, 15)));
+        assert_eq!(a.union(&Interval::new(10, 12)), Some(Interval::new(0, 12)));
+        assert_eq!(a.union(&Interval::new(11, 12)), None);
+    }
+
+    #[test]
+    fn test_interval_difference_and_split() {
+        let a = Interval::new(0, 10);
+        assert_eq!(
+            a.difference(&Interval::new(3, 5)),
+            vec![Interval::new(0, 3), Interval::new(5, 10)]
+        );
+        assert_eq!(
+            a.difference(&Interval::new(-5, 5)),
+            vec![Interval::new(5, 10)]
+        );
+        assert_eq!(a.difference(&Interval::new(-5, 15)), vec![]);
+        assert_eq!(a.difference(&Interval::new(20, 30)), vec![a]);
+
+        assert_eq!(
+            a.split_at(4),
+            (Some(Interval::new(0, 4)), Some(Interval::new(4, 10)))
+        );
+        assert_eq!(a.split_at(0), (None, Some(a)));
+        assert_eq!(a.split_at(15), (Some(a), None));
+    }
+
+    #[test]
+    fn test_interval_set_insert_merges() {
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(10, 20));
+        set.insert(Interval::new(0, 5));
+        set.insert(Interval::new(30, 40));
+        assert_eq!(
+            set.intervals(),
+            &[
+                Interval::new(0, 5),
+                Interval::new(10, 20),
+                Interval::new(30, 40)
+            ]
+        );
+        set.insert(Interval::new(5, 10));
+        assert_eq!(
+            set.intervals(),
+            &[Interval::new(0, 20), Interval::new(30, 40)]
+        );
+        set.insert(Interval::new(15, 35));
+        assert_eq!(set.intervals(), &[Interval::new(0, 40)]);
+        assert_eq!(set.count(), 40);
+    }
+
+    #[test]
+    fn test_interval_set_subtract() {
+        let mut set: IntervalSet<i64> = [Interval::new(0, 10), Interval::new(20, 30)]
+            .into_iter()
+            .collect();
+        set.subtract(&Interval::new(5, 25));
+        assert_eq!(
+            set.intervals(),
+            &[Interval::new(0, 5), Interval::new(25, 30)]
+        );
+        assert_eq!(set.count(), 10);
+        assert!(set.contains(0));
+        assert!(!set.contains(5));
+        assert!(set.contains(29));
+        assert_eq!(set.min(), Some(0));
+        assert_eq!(set.max(), Some(29));
+    }
+
+    #[test]
+    fn test_interval_set_operations() {
+        let left: IntervalSet<i32> = [Interval::new(0, 10), Interval::new(20, 30)]
+            .into_iter()
+            .collect();
+        let right: IntervalSet<i32> = [Interval::new(5, 25)].into_iter().collect();
+        assert_eq!(
+            left.intersection(&right).intervals(),
+            &[Interval::new(5, 10), Interval::new(20, 25)]
+        );
+        assert_eq!(left.union(&right).intervals(), &[Interval::new(0, 30)]);
+        assert_eq!(
+            left.difference(&right).intervals(),
+            &[Interval::new(0, 5), Interval::new(25, 30)]
+        );
+    }
+
+    #[test]
+    fn test_position_steps() {
+        assert!(Position(1, -1).touching(&Position(0, 0)));
+        assert!(!Position(2, 1).touching(&Position(0, 0)));
+        assert_eq!(Position(5, -3).signum(), Position(1, -1));
+        assert_eq!(Position(0, 7).signum(), Direction::Down.offset());
+        let mut position = Position(2, 2);
+        position += Direction::UpLeft.offset();
+        assert_eq!(position, Position(1, 1));
+    }
+
+    fn square() -> Polygon {
+        Polygon::new(vec![
+            Position(0, 0),
+            Position(2, 0),
+            Position(2, 2),
+            Position(0, 2),
+        ])
+    }
+
+    #[test]
+    fn test_polygon_area() {
+        let polygon = square();
+        assert_eq!(polygon.double_area(), 8);
+        assert_eq!(polygon.area(), 4.);
+        assert_eq!(polygon.boundary_points(), 8);
+        assert_eq!(polygon.interior_points(), 1);
+        assert_eq!(polygon.lattice_points(), 9);
+
+        let triangle = Polygon::new(vec![Position(0, 0), Position(4, 0), Position(0, 3)]);
+        assert_eq!(triangle.double_area(), 12);
+        assert_eq!(triangle.boundary_points(), 8);
+        assert_eq!(triangle.interior_points(), 3);
+    }
+
+    #[test]
+    fn test_polygon_from_moves() {
+        // Example from 2023 day18
+        let moves = [
+            (Direction::Right, 6),
+            (Direction::Down, 5),
+            (Direction::Left, 2),
+            (Direction::Down, 2),
+            (Direction::Right, 2),
+            (Direction::Down, 2),
+            (Direction::Left, 5),
+            (Direction::Up, 2),
+            (Direction::Left, 1),
+            (Direction::Up, 2),
+            (Direction::Right, 2),
+            (Direction::Up, 3),
+            (Direction::Left, 2),
+            (Direction::Up, 2),
+        ];
+        let polygon = Polygon::from_moves(Position(0, 0), moves);
+        assert_eq!(polygon.vertices.len(), 14);
+        assert_eq!(polygon.boundary_points(), 38);
+        assert_eq!(polygon.lattice_points(), 62);
+    }
+
+    #[test]
+    fn test_polygon_locate() {
+        let polygon = square();
+        assert_eq!(polygon.locate(&Position(1, 1)), PointLocation::Inside);
+        assert_eq!(polygon.locate(&Position(0, 0)), PointLocation::Boundary);
+        assert_eq!(polygon.locate(&Position(2, 1)), PointLocation::Boundary);
+        assert_eq!(polygon.locate(&Position(3, 1)), PointLocation::Outside);
+        assert_eq!(polygon.locate(&Position(-1, 0)), PointLocation::Outside);
+        assert!(polygon.contains(&Position(1, 2)));
+        assert!(!polygon.contains(&Position(1, 3)));
+
+        // U shaped polygon, the notch is outside
+        let u_shape = Polygon::from_moves(
+            Position(0, 0),
+            [
+                (Direction::Right, 1),
+                (Direction::Down, 2),
+                (Direction::Right, 2),
+                (Direction::Up, 2),
+                (Direction::Right, 1),
+                (Direction::Down, 4),
+                (Direction::Left, 4),
+            ],
+        );
+        assert_eq!(u_shape.locate(&Position(2, 1)), PointLocation::Outside);
+        assert_eq!(u_shape.locate(&Position(2, 3)), PointLocation::Inside);
+        assert_eq!(u_shape.locate(&Position(0, 4)), PointLocation::Boundary);
+    }
 }
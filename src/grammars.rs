@@ -0,0 +1,187 @@
+use crate::parsing::ParseError;
+#[cfg(feature = "year2022")]
+use crate::solutions2022;
+#[cfg(feature = "year2023")]
+use crate::solutions2023;
+use itertools::Itertools;
+use pest::iterators::{Pair, Pairs};
+use pest::{Parser, RuleType};
+use std::path::{Path, PathBuf};
+
+/// A day parsing its input with a pest grammar, and how to dump the parse tree of an input.
+#[derive(Clone, Copy)]
+pub struct Grammar {
+    pub year: u32,
+    pub day: u32,
+    pub parse_tree: fn(&str) -> Result<String, ParseError>,
+}
+
+impl Grammar {
+    pub const fn new(
+        year: u32,
+        day: u32,
+        parse_tree: fn(&str) -> Result<String, ParseError>,
+    ) -> Self {
+        Grammar {
+            year,
+            day,
+            parse_tree,
+        }
+    }
+
+    /// Dumps the parse tree of `input`, telling the day in parse errors.
+    pub fn tree(&self, input: &str) -> Result<String, ParseError> {
+        (self.parse_tree)(input).map_err(|err| err.in_day(self.year, self.day))
+    }
+}
+
+/// Renders parse trees with one pair per line, indented by depth. Pairs without inner pairs
+/// show the text they matched:
+///
+/// ```text
+/// Game
+///   game_id "1"
+///   game_specs
+///     ball_set
+/// ```
+pub fn tree<R: RuleType>(pairs: Pairs<R>) -> String {
+    let mut ret = String::new();
+    for pair in pairs {
+        push_pair(&mut ret, pair, 0);
+    }
+    ret
+}
+
+fn push_pair<R: RuleType>(tree: &mut String, pair: Pair<R>, depth: usize) {
+    let indent = "  ".repeat(depth);
+    let mut inner = pair.clone().into_inner().peekable();
+    if inner.peek().is_none() {
+        *tree += &format!("{indent}{:?} {:?}\n", pair.as_rule(), pair.as_str());
+    } else {
+        *tree += &format!("{indent}{:?}\n", pair.as_rule());
+        for child in inner {
+            push_pair(tree, child, depth + 1);
+        }
+    }
+}
+
+/// Parses `input` from `rule` of the grammar of `P`, and renders its tree.
+pub fn parse_tree<P: Parser<R>, R: RuleType>(rule: R, input: &str) -> Result<String, ParseError> {
+    Ok(tree(P::parse(rule, input)?))
+}
+
+/// Every grammar of the years enabled as features, sorted by year and day.
+pub fn grammars() -> Vec<Grammar> {
+    #[allow(unused_mut)]
+    let mut grammars: Vec<Grammar> = vec![];
+    #[cfg(feature = "year2022")]
+    grammars.extend(solutions2022::grammars());
+    #[cfg(feature = "year2023")]
+    grammars.extend(solutions2023::grammars());
+    grammars
+        .into_iter()
+        .sorted_by_key(|grammar| (grammar.year, grammar.day))
+        .collect_vec()
+}
+
+pub fn find(year: u32, day: u32) -> Option<Grammar> {
+    grammars()
+        .into_iter()
+        .find(|grammar| grammar.year == year && grammar.day == day)
+}
+
+/// Where the parse tree of an example is snapshotted: next to it, like
+/// `inputs/2023/day2.example.tree.txt` for `inputs/2023/day2.example.txt`.
+pub fn snapshot_path(example_path: &Path) -> PathBuf {
+    example_path.with_extension("tree.txt")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::examples::examples;
+    use crate::registry;
+    use expect_test::{expect, expect_file};
+    use std::fs;
+
+    #[derive(pest_derive::Parser)]
+    #[grammar_inline = r#"
+        number = { ASCII_DIGIT+ }
+        pair = { number ~ "," ~ number }
+        Grammar = _{ SOI ~ pair ~ (NEWLINE ~ pair)* ~ EOI }
+    "#]
+    struct PairsParser;
+
+    #[test]
+    fn test_parse_tree() {
+        let tree = parse_tree::<PairsParser, _>(Rule::Grammar, "1,2\n3,45");
+        expect![[r#"
+            pair
+              number "1"
+              number "2"
+            pair
+              number "3"
+              number "45"
+            EOI ""
+        "#]]
+        .assert_eq(&tree.unwrap());
+
+        let error = parse_tree::<PairsParser, _>(Rule::Grammar, "1,2\n3").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+    }
+
+    /// Snapshots the parse tree of every example of every grammar. Run with `UPDATE_EXPECT=1` to
+    /// update the snapshots after changing a grammar, and review their diff.
+    #[test]
+    fn test_example_trees() {
+        for grammar in grammars() {
+            let examples = examples(grammar.year, grammar.day).unwrap();
+            assert!(
+                !examples.is_empty(),
+                "No examples for {} day {}",
+                grammar.year,
+                grammar.day
+            );
+            for example in examples {
+                let tree = grammar
+                    .tree(&example.input)
+                    .unwrap_or_else(|err| panic!("{}: {err}", example.path.display()));
+                let snapshot =
+                    Path::new(env!("CARGO_MANIFEST_DIR")).join(snapshot_path(&example.path));
+                expect_file![snapshot].assert_eq(&tree);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_grammar_is_registered() {
+        let years = registry::solutions()
+            .iter()
+            .map(|solution| solution.year)
+            .unique()
+            .collect_vec();
+        for year in years {
+            let dir = format!("src/solutions{year}");
+            let days = fs::read_dir(&dir)
+                .unwrap()
+                .flatten()
+                .filter_map(|entry| {
+                    let name = entry.file_name().to_string_lossy().to_string();
+                    name.strip_prefix("day")?
+                        .strip_suffix(".pest")?
+                        .parse::<u32>()
+                        .ok()
+                })
+                .sorted()
+                .collect_vec();
+            let registered = grammars()
+                .iter()
+                .filter(|grammar| grammar.year == year)
+                .map(|grammar| grammar.day)
+                .collect_vec();
+            assert_eq!(registered, days, "Grammars of {dir}");
+        }
+        assert!(find(2023, 2).is_some() == cfg!(feature = "year2023"));
+        assert!(find(2023, 1).is_none());
+    }
+}
@@ -0,0 +1,32 @@
+//! Solutions to Advent of Code puzzles, and the helpers they are built with.
+//!
+//! The utility modules (`containers`, `geometry`, `array`, `strings`, `files`) don't depend on
+//! the solutions and can be used on their own. The solved days are reached through
+//! [registry::solutions]. Each year is behind a cargo feature of the same name, like `year2023`,
+//! all enabled by default.
+
+pub mod containers;
+#[cfg(feature = "year2022")]
+mod solutions2022;
+#[cfg(feature = "year2023")]
+mod solutions2023;
+pub mod files;
+pub mod geometry;
+pub mod strings;
+pub mod array;
+pub mod examples;
+#[cfg(feature = "grammar")]
+pub mod grammars;
+pub mod registry;
+pub mod scaffold;
+pub mod client;
+pub mod submit;
+pub mod watch;
+pub mod runner;
+pub mod logging;
+pub mod dumps;
+pub mod parsing;
+#[cfg(test)]
+mod asserts;
+#[cfg(test)]
+mod stub_server;
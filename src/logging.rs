@@ -0,0 +1,46 @@
+use env_logger::Builder;
+use std::env;
+
+const CRATE: &str = env!("CARGO_CRATE_NAME");
+
+/// Target of the logs of a day: the path of its module, like `advent_of_code::solutions2023::day6`.
+pub fn day_target(year: u32, day: u32) -> String {
+    format!("{CRATE}::solutions{year}::day{day}")
+}
+
+/// Filter in the `RUST_LOG` syntax for a number of `-v` flags: warnings only, then debug, then
+/// trace logs of `target`, or of the whole crate. Other crates stay at warnings.
+fn filters(verbosity: u8, target: Option<&str>) -> String {
+    let level = match verbosity {
+        0 => return "warn".to_string(),
+        1 => "debug",
+        _ => "trace",
+    };
+    format!("warn,{}={level}", target.unwrap_or(CRATE))
+}
+
+/// Sends logs to stderr, so that they don't mix with the answers. `RUST_LOG`, when set, takes
+/// precedence over `verbosity` and `target`.
+pub fn init(verbosity: u8, target: Option<&str>) {
+    let filters = env::var("RUST_LOG").unwrap_or_else(|_| filters(verbosity, target));
+    Builder::new()
+        .parse_filters(&filters)
+        .format_timestamp(None)
+        .init();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_filters() {
+        assert_eq!(filters(0, None), "warn");
+        assert_eq!(filters(1, None), "warn,advent_of_code=debug");
+        let target = day_target(2022, 11);
+        assert_eq!(
+            filters(3, Some(&target)),
+            "warn,advent_of_code::solutions2022::day11=trace"
+        );
+    }
+}
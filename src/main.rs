@@ -1,57 +1,324 @@
-use crate::solutions2022::day1::part1;
-use crate::solutions2022::day1::part2;
-use crate::solutions2022::day2::{day2part1, day2part2};
-use crate::solutions2022::day3::{day3part1, day3part2};
-use crate::solutions2022::day4::{day4part1, day4part2};
-use crate::solutions2022::day5::day5;
-use crate::solutions2022::day6::day6;
-use crate::solutions2022::day7::day7;
-use crate::solutions2022::day8::day8;
-use crate::solutions2022::day9::day9;
-use crate::solutions2022::day10::day10;
-use crate::solutions2022::day11::day11;
-
-mod containers;
-mod solutions2022;
-mod solutions2023;
-mod files;
-mod geometry;
-mod strings;
-mod array;
-mod asserts;
-
-fn main() {
-    // println!("Day1 part1: {}", part1());
-    // println!("Day1 part2: {}", part2());
-    //
-    // println!("Day2 part1: {}", day2part1());
-    // println!("Day2 part2: {}", day2part2());
-    //
-    // println!("Day3 part1: {}", day3part1());
-    // println!("Day3 part2: {}", day3part2());
-    //
-    // println!("Day4 part1: {}", day4part1());
-    // println!("Day4 part2: {}", day4part2());
-    //
-    // println!("Day5: {:?}", day5());
-    // println!("Day6: {:?}", day6());
-    // println!("Day7: {:?}", day7());
-    // println!("Day8: {:?}", day8());
-    // println!("Day9: {:?}", day9());
-    // let day10_answer = day10();
-    // println!("Day10: {:?}", day10_answer.0);
-    // println!("{}", day10_answer.1);
-
-    // println!("Day11: {:?}", day11());
+use advent_of_code::client::{Client, Fetched, RateLimiter};
+use advent_of_code::examples::examples;
+#[cfg(feature = "grammar")]
+use advent_of_code::files::puzzle_input;
+#[cfg(feature = "grammar")]
+use advent_of_code::grammars;
+use advent_of_code::registry::Solution;
+use advent_of_code::submit::{Ledger, Outcome};
+use advent_of_code::{client, dumps, logging, registry, runner, scaffold, submit, watch};
+use anyhow::{bail, Context};
+use clap::{value_parser, Arg, ArgAction, ArgMatches, Command};
+use itertools::Itertools;
+use std::env;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_YEAR: u32 = 2023;
+
+fn year_arg() -> Arg {
+    Arg::new("year").long("year").value_parser(value_parser!(u32))
+}
+
+fn day_arg() -> Arg {
+    Arg::new("day").long("day").value_parser(value_parser!(u32))
+}
+
+/// Arguments of the commands talking to the server, which default to environment variables.
+fn server_args() -> [Arg; 2] {
+    [
+        Arg::new("session")
+            .long("session")
+            .help("Session cookie of the logged in account, defaults to $AOC_SESSION"),
+        Arg::new("base-url")
+            .long("base-url")
+            .help("Server to talk to, defaults to $AOC_BASE_URL or the Advent of Code website"),
+    ]
+}
+
+fn cli() -> Command {
+    let run = Command::new("run")
+        .about("Solves every day of a year, or a single day")
+        .arg(year_arg())
+        .arg(day_arg())
+        .arg(
+            Arg::new("example")
+                .long("example")
+                .action(ArgAction::SetTrue)
+                .help("Solve the examples and check their answers instead of the puzzle input"),
+        )
+        .arg(
+            Arg::new("watch")
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .requires("day")
+                .conflicts_with("example")
+                .help("Rebuild, test and solve the day again whenever its files change"),
+        )
+        .arg(
+            Arg::new("all")
+                .long("all")
+                .action(ArgAction::SetTrue)
+                .conflicts_with_all(["day", "example", "watch"])
+                .help("Solve the days of every year, or of --year, in parallel and report timings"),
+        )
+        .arg(
+            Arg::new("jobs")
+                .long("jobs")
+                .requires("all")
+                .value_parser(value_parser!(usize))
+                .help("Number of days solved at once, defaults to the number of cores"),
+        )
+        .arg(
+            Arg::new("dump-dir")
+                .long("dump-dir")
+                .value_parser(value_parser!(PathBuf))
+                .help("Write debugging dumps of the days, like 2022 day 9 frames, in this directory"),
+        );
+    let new = Command::new("new")
+        .about("Generates the module, registration and input files of a new day")
+        .arg(year_arg().required(true))
+        .arg(day_arg().required(true))
+        .arg(
+            Arg::new("grammar")
+                .long("grammar")
+                .action(ArgAction::SetTrue)
+                .help("Also generate a .pest grammar and parse the input with it"),
+        );
+    let fetch = Command::new("fetch")
+        .about("Downloads the puzzle input of a day, unless it is already in inputs/")
+        .arg(year_arg().required(true))
+        .arg(day_arg().required(true))
+        .args(server_args());
+    let submit = Command::new("submit")
+        .about("Submits the answer to a part of a day, unless it is known to be wrong")
+        .arg(year_arg().required(true))
+        .arg(day_arg().required(true))
+        .arg(
+            Arg::new("part")
+                .long("part")
+                .required(true)
+                .value_parser(value_parser!(u8).range(1..=2)),
+        )
+        .arg(
+            Arg::new("answer")
+                .long("answer")
+                .help("Answer to submit, defaults to the one computed from the puzzle input"),
+        )
+        .args(server_args());
+    let parse = Command::new("parse")
+        .about("Prints the parse tree of a day's input with its pest grammar")
+        .arg(year_arg().required(true))
+        .arg(day_arg().required(true))
+        .arg(
+            Arg::new("example")
+                .long("example")
+                .action(ArgAction::SetTrue)
+                .help("Print the trees of the examples instead of the puzzle input"),
+        );
+    Command::new("advent-of-code")
+        .arg(
+            Arg::new("verbose")
+                .short('v')
+                .long("verbose")
+                .action(ArgAction::Count)
+                .global(true)
+                .help("Log what the solutions do, or only the day given to --day, -vv for more"),
+        )
+        .subcommand(run)
+        .subcommand(new)
+        .subcommand(fetch)
+        .subcommand(submit)
+        .subcommand(parse)
+}
+
+fn main() -> anyhow::Result<()> {
+    let matches = cli().get_matches();
+    init_logging(&matches);
+    match matches.subcommand() {
+        Some(("run", matches)) => run(matches),
+        Some(("new", matches)) => new_day(matches),
+        Some(("fetch", matches)) => fetch(matches),
+        Some(("submit", matches)) => submit(matches),
+        Some(("parse", matches)) => parse(matches),
+        _ => run_year(DEFAULT_YEAR, None, false),
+    }
+}
+
+/// Logs of a single day when one is given to `run`, of every day otherwise.
+fn init_logging(matches: &ArgMatches) {
+    let (verbosity, target) = match matches.subcommand() {
+        Some(("run", matches)) => {
+            let year = matches.get_one::<u32>("year").copied().unwrap_or(DEFAULT_YEAR);
+            let target = matches
+                .get_one::<u32>("day")
+                .map(|day| logging::day_target(year, *day));
+            (matches.get_count("verbose"), target)
+        }
+        Some((_, matches)) => (matches.get_count("verbose"), None),
+        None => (matches.get_count("verbose"), None),
+    };
+    logging::init(verbosity, target.as_deref());
+}
+
+fn run(matches: &ArgMatches) -> anyhow::Result<()> {
+    if let Some(dir) = matches.get_one::<PathBuf>("dump-dir") {
+        dumps::init(dir.clone())?;
+    }
+    if matches.get_flag("all") {
+        return run_all(matches);
+    }
+    let year = matches.get_one::<u32>("year").copied().unwrap_or(DEFAULT_YEAR);
+    let day = matches.get_one::<u32>("day").copied();
+    if matches.get_flag("watch") {
+        return watch::watch(year, day.unwrap());
+    }
+    run_year(year, day, matches.get_flag("example"))
+}
+
+fn run_all(matches: &ArgMatches) -> anyhow::Result<()> {
+    let year = matches.get_one::<u32>("year");
+    let solutions = registry::solutions()
+        .into_iter()
+        .filter(|solution| year.is_none_or(|year| solution.year == *year))
+        .collect_vec();
+    let jobs = matches.get_one::<usize>("jobs").copied();
+    let reports = runner::run_all(&solutions, jobs, Solution::run)?;
+    print!("{}", runner::summary(&reports));
+    Ok(())
+}
+
+fn new_day(matches: &ArgMatches) -> anyhow::Result<()> {
+    let year = *matches.get_one::<u32>("year").unwrap();
+    let day = *matches.get_one::<u32>("day").unwrap();
+    let created = scaffold::new_day(Path::new("."), year, day, matches.get_flag("grammar"))?;
+    for path in created {
+        println!("{}", path.display());
+    }
+    Ok(())
+}
+
+fn client(matches: &ArgMatches) -> anyhow::Result<Client> {
+    let session = match matches.get_one::<String>("session") {
+        Some(session) => session.clone(),
+        None => env::var("AOC_SESSION").context("Pass --session or set $AOC_SESSION")?,
+    };
+    let base_url = match matches.get_one::<String>("base-url") {
+        Some(base_url) => base_url.clone(),
+        None => env::var("AOC_BASE_URL").unwrap_or(client::DEFAULT_BASE_URL.to_string()),
+    };
+    let state_file = Path::new(client::STATE_DIR).join("last-request");
+    let rate_limiter = RateLimiter::new(client::DEFAULT_MIN_INTERVAL, state_file);
+    Ok(Client::new(&base_url, &session, rate_limiter))
+}
+
+fn fetch(matches: &ArgMatches) -> anyhow::Result<()> {
+    let year = *matches.get_one::<u32>("year").unwrap();
+    let day = *matches.get_one::<u32>("day").unwrap();
+    match client::fetch_input(&client(matches)?, Path::new("."), year, day)? {
+        Fetched::Cached(path) => println!("Already fetched: {}", path.display()),
+        Fetched::Downloaded(path) => println!("Downloaded {}", path.display()),
+    }
+    Ok(())
+}
+
+fn submit(matches: &ArgMatches) -> anyhow::Result<()> {
+    let year = *matches.get_one::<u32>("year").unwrap();
+    let day = *matches.get_one::<u32>("day").unwrap();
+    let part = *matches.get_one::<u8>("part").unwrap();
+    let answer = match matches.get_one::<String>("answer") {
+        Some(answer) => answer.clone(),
+        None => {
+            let solution = registry::find(year, day)
+                .with_context(|| format!("No solution for {year} day {day}"))?;
+            let (part1, part2) = solution.run()?;
+            if part == 1 { part1 } else { part2 }
+        }
+    };
+    let mut ledger = Ledger::load(Path::new(client::STATE_DIR).join("ledger.tsv"))?;
+    let outcome = submit::submit(&client(matches)?, &mut ledger, year, day, part, &answer)?;
+    match outcome {
+        Outcome::Correct => println!("{answer} is the right answer"),
+        Outcome::TooHigh => println!("{answer} is too high"),
+        Outcome::TooLow => println!("{answer} is too low"),
+        Outcome::Wrong => println!("{answer} is not the right answer"),
+        Outcome::Wait(seconds) => println!("Submitted too recently, wait {seconds}s"),
+        Outcome::WrongLevel => println!("Part {part} is already solved or not unlocked yet"),
+    }
+    Ok(())
+}
+
+#[cfg(feature = "grammar")]
+fn parse(matches: &ArgMatches) -> anyhow::Result<()> {
+    let year = *matches.get_one::<u32>("year").unwrap();
+    let day = *matches.get_one::<u32>("day").unwrap();
+    let grammar =
+        grammars::find(year, day).with_context(|| format!("No grammar for {year} day {day}"))?;
+    if matches.get_flag("example") {
+        for example in examples(year, day)? {
+            println!("{}:", example.path.display());
+            print!("{}", grammar.tree(&example.input)?);
+        }
+    } else {
+        print!("{}", grammar.tree(&puzzle_input(&registry::input_path(year, day)))?);
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "grammar"))]
+fn parse(_: &ArgMatches) -> anyhow::Result<()> {
+    bail!("Built without grammars, enable the grammar feature")
+}
+
+fn run_year(year: u32, day: Option<u32>, example: bool) -> anyhow::Result<()> {
+    let solutions = match day {
+        Some(day) => registry::find(year, day).into_iter().collect_vec(),
+        None => registry::solutions()
+            .into_iter()
+            .filter(|solution| solution.year == year)
+            .collect_vec(),
+    };
+    if solutions.is_empty() {
+        match day {
+            Some(day) => bail!("No solution for {year} day {day}"),
+            None => bail!("No solutions for {year}"),
+        }
+    }
 
     println!("********");
-    println!("2023");
+    println!("{year}");
     println!("********");
-    println!("Day1: {:?}", solutions2023::day1::solution());
-    println!("Day2: {:?}", solutions2023::day2::solution());
-    println!("Day3: {:?}", solutions2023::day3::solution());
-    println!("Day4: {:?}", solutions2023::day4::solution());
-    // Day5 needs to run with cargo run --release
-    // println!("Day5: {:?}", solutions2023::day5::solution());
-    println!("Day6: {:?}", solutions2023::day6::solution());
+    for solution in solutions {
+        if example {
+            print_examples(solution)?;
+        } else {
+            match solution.run() {
+                Ok((part1, part2)) => println!("Day{}: ({part1}, {part2})", solution.day),
+                Err(err) => println!("Day{}: {err:#}", solution.day),
+            }
+        }
+    }
+    Ok(())
+}
+
+fn print_examples(solution: Solution) -> anyhow::Result<()> {
+    let examples = examples(solution.year, solution.day)?;
+    if examples.is_empty() {
+        println!("Day{}: no examples", solution.day);
+    }
+    for example in examples {
+        let path = example.path.display();
+        match solution.solve_input(&example.input) {
+            Ok(answers) => {
+                let mismatches = example.mismatches(&answers);
+                let status = if mismatches.is_empty() {
+                    "ok".to_string()
+                } else {
+                    mismatches.join(", ")
+                };
+                println!("Day{} {path}: ({}, {}) {status}", solution.day, answers.0, answers.1);
+            }
+            Err(err) => println!("Day{} {path}: {err:#}", solution.day),
+        }
+    }
+    Ok(())
 }
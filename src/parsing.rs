@@ -0,0 +1,320 @@
+use crate::strings::{IntegersError, IntegersErrorKind};
+use std::fmt::{Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
+
+/// Error of a puzzle input parser, pointing at the offending spot of the input.
+///
+/// Parsers usually only know the message, and sometimes the column, of an error: the line is
+/// added by [parse_lines] or [ParseResult::on_line], the year and day by the registry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub year: Option<u32>,
+    pub day: Option<u32>,
+    /// Line number, from 1.
+    pub line: Option<usize>,
+    /// Column in characters, from 1.
+    pub column: Option<usize>,
+    /// The offending line.
+    pub text: Option<String>,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn new(message: impl Display) -> Self {
+        ParseError {
+            year: None,
+            day: None,
+            line: None,
+            column: None,
+            text: None,
+            message: message.to_string(),
+        }
+    }
+
+    /// Places the error on line `number` of the input, unless it was already placed.
+    pub fn on_line(mut self, number: usize, text: &str) -> Self {
+        if self.line.is_none() {
+            self.line = Some(number);
+            self.text = Some(text.to_string());
+        }
+        self
+    }
+
+    /// Places the error at `token`, a part of `text`, unless it was already placed on a column.
+    /// Columns are found the most precisely when `token` is a slice of `text`.
+    pub fn at(mut self, text: &str, token: &str) -> Self {
+        if self.column.is_none() {
+            let offset = slice_offset(text, token).or_else(|| text.find(token));
+            self.column = offset.map(|offset| text[..offset].chars().count() + 1);
+            self.text = Some(text.to_string());
+        }
+        self
+    }
+
+    pub fn in_day(mut self, year: u32, day: u32) -> Self {
+        self.year.get_or_insert(year);
+        self.day.get_or_insert(day);
+        self
+    }
+
+    fn location(&self) -> Vec<String> {
+        let day = match (self.year, self.day) {
+            (Some(year), Some(day)) => Some(format!("{year} day {day}")),
+            _ => None,
+        };
+        let line = self.line.map(|line| format!("line {line}"));
+        let column = self.column.map(|column| format!("column {column}"));
+        [day, line, column].into_iter().flatten().collect()
+    }
+}
+
+/// Offset of `token` in `text` when it is one of its slices.
+fn slice_offset(text: &str, token: &str) -> Option<usize> {
+    let start = text.as_ptr() as usize;
+    let offset = (token.as_ptr() as usize).checked_sub(start)?;
+    (offset + token.len() <= text.len()).then_some(offset)
+}
+
+/// Renders the location and the message, then the offending line with a caret under the column:
+///
+/// ```text
+/// 2022 day 4, line 2, column 3: invalid number 'x': invalid digit found in string
+///   |
+/// 2 | 2-x,6-8
+///   |   ^
+/// ```
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let location = self.location();
+        if location.is_empty() {
+            write!(f, "{}", self.message)?;
+        } else {
+            write!(f, "{}: {}", location.join(", "), self.message)?;
+        }
+        if let Some(text) = &self.text {
+            let number = self.line.map(|line| line.to_string()).unwrap_or_default();
+            let gutter = " ".repeat(number.len());
+            write!(f, "\n{gutter} |\n{number} | {}", text.trim_end())?;
+            if let Some(column) = self.column {
+                write!(f, "\n{gutter} | {}^", " ".repeat(column - 1))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseIntError> for ParseError {
+    fn from(err: ParseIntError) -> Self {
+        ParseError::new(err)
+    }
+}
+
+impl From<IntegersError> for ParseError {
+    fn from(err: IntegersError) -> Self {
+        let message = match &err.kind {
+            IntegersErrorKind::Invalid { text, source } => {
+                format!("invalid integer '{text}': {source}")
+            }
+            IntegersErrorKind::WrongCount { expected, found } => {
+                format!("expected {expected} integers, found {found}")
+            }
+        };
+        let error = match &err.kind {
+            IntegersErrorKind::Invalid { text, .. } => ParseError::new(message).at(&err.line, text),
+            IntegersErrorKind::WrongCount { .. } => ParseError::new(message),
+        };
+        match err.line_number {
+            Some(line_number) => error.on_line(line_number, &err.line),
+            None => ParseError {
+                text: Some(err.line),
+                ..error
+            },
+        }
+    }
+}
+
+#[cfg(feature = "grammar")]
+impl<R: pest::RuleType> From<pest::error::Error<R>> for ParseError {
+    fn from(err: pest::error::Error<R>) -> Self {
+        use pest::error::LineColLocation;
+
+        let (line, column) = match err.line_col {
+            LineColLocation::Pos(position) => position,
+            LineColLocation::Span(start, _) => start,
+        };
+        ParseError {
+            line: Some(line),
+            column: Some(column),
+            text: Some(err.line().to_string()),
+            ..ParseError::new(err.variant.message())
+        }
+    }
+}
+
+#[cfg(feature = "grammar")]
+impl ParseError {
+    /// Places the error at the start of `span`, a part of a whole input parsed by a grammar.
+    pub fn at_span(mut self, span: pest::Span) -> Self {
+        if self.line.is_none() {
+            let start = span.start_pos();
+            let (line, column) = start.line_col();
+            self.line = Some(line);
+            self.column = Some(column);
+            self.text = Some(start.line_of().to_string());
+        }
+        self
+    }
+}
+
+/// Places the errors of results, like the methods of the same name of [ParseError].
+pub trait ParseResult<T> {
+    fn on_line(self, number: usize, text: &str) -> Result<T, ParseError>;
+
+    fn at(self, text: &str, token: &str) -> Result<T, ParseError>;
+
+    #[cfg(feature = "grammar")]
+    fn at_span(self, span: pest::Span) -> Result<T, ParseError>;
+}
+
+impl<T, E: Into<ParseError>> ParseResult<T> for Result<T, E> {
+    fn on_line(self, number: usize, text: &str) -> Result<T, ParseError> {
+        self.map_err(|err| err.into().on_line(number, text))
+    }
+
+    fn at(self, text: &str, token: &str) -> Result<T, ParseError> {
+        self.map_err(|err| err.into().at(text, token))
+    }
+
+    #[cfg(feature = "grammar")]
+    fn at_span(self, span: pest::Span) -> Result<T, ParseError> {
+        self.map_err(|err| err.into().at_span(span))
+    }
+}
+
+/// Parses `token`, a part of `text`, as a number, pointing at it on failure.
+pub fn number<T: FromStr>(text: &str, token: &str) -> Result<T, ParseError>
+where
+    T::Err: Display,
+{
+    token.trim().parse::<T>().map_err(|err| {
+        let message = format!("invalid number '{token}': {err}");
+        ParseError::new(message).at(text, token)
+    })
+}
+
+/// Parses every non-blank line of `input` with `parse_line`, placing errors on their line.
+pub fn parse_lines<'a, T>(
+    input: &'a str,
+    mut parse_line: impl FnMut(&'a str) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_line(line).on_line(i + 1, line))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings::{dedent, integers_array, integers_per_line};
+
+    fn parse_interval(line: &str) -> Result<(i32, i32), ParseError> {
+        let (start, end) = line
+            .split_once('-')
+            .ok_or_else(|| ParseError::new("expected an interval like 2-4"))?;
+        Ok((number(line, start)?, number(line, end)?))
+    }
+
+    #[test]
+    fn test_parse_lines() {
+        assert_eq!(
+            parse_lines("2-4\n\n6-8", parse_interval),
+            Ok(vec![(2, 4), (6, 8)])
+        );
+
+        let error = parse_lines("2-4\n6-x8\n", parse_interval)
+            .unwrap_err()
+            .in_day(2022, 4);
+        assert_eq!(
+            error.to_string(),
+            dedent(
+                "
+                2022 day 4, line 2, column 3: invalid number 'x8': invalid digit found in string
+                  |
+                2 | 6-x8
+                  |   ^"
+            )
+            .trim_start()
+        );
+
+        let error = parse_lines("2-4\n68", parse_interval).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected an interval like 2-4\n  |\n2 | 68"
+        );
+    }
+
+    #[test]
+    fn test_at() {
+        let error = ParseError::new("bad").at("é b c", "c");
+        assert_eq!(error.column, Some(5));
+        // Not a slice of the line, found by searching it
+        let token = String::from("b");
+        let error = ParseError::new("bad").at("a b c", &token);
+        assert_eq!(error.column, Some(3));
+        let error = ParseError::new("bad").at("a b c", "d");
+        assert_eq!(error.column, None);
+        assert_eq!(error.to_string(), "bad\n |\n | a b c");
+    }
+
+    #[test]
+    fn test_from_parse_int_error() {
+        let error: ParseError = "12a".parse::<u8>().unwrap_err().into();
+        assert_eq!(error.to_string(), "invalid digit found in string");
+        let error = ParseError::from("300".parse::<u8>().unwrap_err()).on_line(7, "300");
+        assert_eq!(error.line, Some(7));
+    }
+
+    #[test]
+    fn test_from_integers_error() {
+        let error: ParseError = integers_array::<u32, 3>("move 1 from 2")
+            .unwrap_err()
+            .into();
+        assert_eq!(
+            error.to_string(),
+            "expected 3 integers, found 2\n |\n | move 1 from 2"
+        );
+        let error: ParseError = integers_per_line::<u8>("1 2\n3 400").unwrap_err().into();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: invalid integer '400': number too large to fit in target type\n  |\n2 | 3 400\n  |   ^"
+        );
+    }
+
+    #[cfg(feature = "grammar")]
+    #[derive(pest_derive::Parser)]
+    #[grammar_inline = r#"
+        number = { ASCII_DIGIT+ }
+        Grammar = _{ SOI ~ number ~ ((NEWLINE | ",") ~ number)* ~ EOI }
+    "#]
+    struct NumbersParser;
+
+    #[cfg(feature = "grammar")]
+    #[test]
+    fn test_from_pest_error() {
+        use pest::Parser;
+
+        let error: ParseError = NumbersParser::parse(Rule::Grammar, "1,2\n3,x4")
+            .unwrap_err()
+            .into();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected number\n  |\n2 | 3,x4\n  |   ^"
+        );
+    }
+}
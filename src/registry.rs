@@ -0,0 +1,90 @@
+use crate::files::puzzle_input;
+use crate::parsing::ParseError;
+#[cfg(feature = "year2022")]
+use crate::solutions2022;
+#[cfg(feature = "year2023")]
+use crate::solutions2023;
+use anyhow::Result;
+use itertools::Itertools;
+use std::fmt::Display;
+
+/// Answers to both parts of a puzzle, rendered the way they are submitted.
+pub type Answers = (String, String);
+
+pub fn answers(part1: impl Display, part2: impl Display) -> Answers {
+    (part1.to_string(), part2.to_string())
+}
+
+/// A solved day: where its input lives and how to solve it.
+#[derive(Clone, Copy)]
+pub struct Solution {
+    pub year: u32,
+    pub day: u32,
+    pub solve: fn(&str) -> Result<Answers>,
+}
+
+impl Solution {
+    pub const fn new(year: u32, day: u32, solve: fn(&str) -> Result<Answers>) -> Self {
+        Solution { year, day, solve }
+    }
+
+    pub fn input_path(&self) -> String {
+        input_path(self.year, self.day)
+    }
+
+    /// Solves the day's puzzle input.
+    pub fn run(&self) -> Result<Answers> {
+        self.solve_input(&puzzle_input(&self.input_path()))
+    }
+
+    /// Solves `input`, telling the day in its parse errors.
+    pub fn solve_input(&self, input: &str) -> Result<Answers> {
+        (self.solve)(input).map_err(|err| match err.downcast::<ParseError>() {
+            Ok(err) => err.in_day(self.year, self.day).into(),
+            Err(err) => err,
+        })
+    }
+}
+
+/// Where the puzzle input of a day lives, relative to the repository root.
+pub fn input_path(year: u32, day: u32) -> String {
+    format!("inputs/{year}/day{day}.txt")
+}
+
+/// Every solved day of the years enabled as features, sorted by year and day.
+pub fn solutions() -> Vec<Solution> {
+    #[allow(unused_mut)]
+    let mut solutions: Vec<Solution> = vec![];
+    #[cfg(feature = "year2022")]
+    solutions.extend(solutions2022::solutions());
+    #[cfg(feature = "year2023")]
+    solutions.extend(solutions2023::solutions());
+    solutions
+        .into_iter()
+        .sorted_by_key(|solution| (solution.year, solution.day))
+        .collect_vec()
+}
+
+pub fn find(year: u32, day: u32) -> Option<Solution> {
+    solutions()
+        .into_iter()
+        .find(|solution| solution.year == year && solution.day == day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solutions_are_unique() {
+        let solutions = solutions();
+        let days = solutions
+            .iter()
+            .map(|solution| (solution.year, solution.day))
+            .collect_vec();
+        assert_eq!(days.iter().unique().count(), days.len(), "{days:?}");
+        assert_eq!(find(2023, 4).is_some(), cfg!(feature = "year2023"));
+        assert_eq!(find(2022, 1).is_some(), cfg!(feature = "year2022"));
+        assert!(find(2023, 26).is_none());
+    }
+}
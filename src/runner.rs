@@ -0,0 +1,260 @@
+use crate::registry::{Answers, Solution};
+use anyhow::Result;
+use itertools::Itertools;
+use rayon::prelude::*;
+use std::any::Any;
+use std::panic;
+use std::panic::AssertUnwindSafe;
+use std::time::{Duration, Instant};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Status {
+    Solved(Answers),
+    Failed(String),
+    Panicked(String),
+}
+
+/// How solving one day went.
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub year: u32,
+    pub day: u32,
+    pub status: Status,
+    pub elapsed: Duration,
+}
+
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<String>() {
+        Ok(message) => *message,
+        Err(payload) => match payload.downcast::<&str>() {
+            Ok(message) => message.to_string(),
+            Err(_) => "unknown panic".to_string(),
+        },
+    }
+}
+
+/// Runs one day, turning its errors and panics into a status so that they don't stop the others.
+fn run_isolated(
+    solution: &Solution,
+    run: &(impl Fn(&Solution) -> Result<Answers> + Sync),
+) -> Report {
+    let start = Instant::now();
+    let status = match panic::catch_unwind(AssertUnwindSafe(|| run(solution))) {
+        Ok(Ok(answers)) => Status::Solved(answers),
+        Ok(Err(err)) => Status::Failed(format!("{err:#}")),
+        Err(payload) => Status::Panicked(panic_message(payload)),
+    };
+    Report {
+        year: solution.year,
+        day: solution.day,
+        status,
+        elapsed: start.elapsed(),
+    }
+}
+
+/// Solves every day with `run` on a pool of `jobs` threads, all cores by default. Reports are
+/// sorted from the slowest day to the fastest.
+pub fn run_all(
+    solutions: &[Solution],
+    jobs: Option<usize>,
+    run: impl Fn(&Solution) -> Result<Answers> + Sync,
+) -> Result<Vec<Report>> {
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .build()?;
+    let reports = pool.install(|| {
+        solutions
+            .par_iter()
+            .map(|solution| run_isolated(solution, &run))
+            .collect::<Vec<_>>()
+    });
+    Ok(reports
+        .into_iter()
+        .sorted_by_key(|report| std::cmp::Reverse(report.elapsed))
+        .collect_vec())
+}
+
+/// Multi-line answers, like letters drawn on a screen, don't fit in a table cell.
+fn cell(answer: &str) -> String {
+    match answer.lines().count() {
+        0 | 1 => answer.to_string(),
+        lines => format!("({lines} lines)"),
+    }
+}
+
+/// Longest status shown in the table.
+const STATUS_WIDTH: usize = 60;
+
+/// First line of an error or panic message, cut to fit in the table. Messages that don't fit are
+/// printed in full below it.
+fn status_cell(kind: &str, message: &str) -> String {
+    let first_line = message.lines().next().unwrap_or_default();
+    let mut cell = format!("{kind}: {first_line}");
+    if cell.chars().count() > STATUS_WIDTH {
+        cell = cell.chars().take(STATUS_WIDTH - 3).collect::<String>() + "...";
+    } else if first_line != message {
+        cell += " ...";
+    }
+    cell
+}
+
+/// Renders the reports as a table with one row per day, followed by the total time spent and by
+/// the messages too long for the table.
+pub fn summary(reports: &[Report]) -> String {
+    let header = ["Year", "Day", "Time", "Status", "Part 1", "Part 2"].map(String::from);
+    let rows = reports
+        .iter()
+        .map(|report| {
+            let (status, part1, part2) = match &report.status {
+                Status::Solved((part1, part2)) => ("ok".to_string(), cell(part1), cell(part2)),
+                Status::Failed(err) => (status_cell("error", err), String::new(), String::new()),
+                Status::Panicked(message) => {
+                    (status_cell("panic", message), String::new(), String::new())
+                }
+            };
+            [
+                report.year.to_string(),
+                report.day.to_string(),
+                format!("{:.1?}", report.elapsed),
+                status,
+                part1,
+                part2,
+            ]
+        })
+        .collect_vec();
+    let widths = (0..header.len())
+        .map(|column| {
+            rows.iter()
+                .chain([&header])
+                .map(|row| row[column].chars().count())
+                .max()
+                .unwrap_or(0)
+        })
+        .collect_vec();
+
+    let mut table = String::new();
+    for row in [&header].into_iter().chain(&rows) {
+        let line = row
+            .iter()
+            .zip(&widths)
+            .map(|(cell, width)| format!("{cell:<width$}"))
+            .join("  ");
+        table += line.trim_end();
+        table += "\n";
+    }
+    let total: Duration = reports.iter().map(|report| report.elapsed).sum();
+    let failed = reports
+        .iter()
+        .filter(|report| !matches!(report.status, Status::Solved(_)))
+        .count();
+    table += &format!(
+        "{} days, {failed} failed, {:.1?} of solving time\n",
+        reports.len(),
+        total
+    );
+    for report in reports {
+        let (kind, message) = match &report.status {
+            Status::Solved(_) => continue,
+            Status::Failed(err) => ("error", err),
+            Status::Panicked(message) => ("panic", message),
+        };
+        if status_cell(kind, message) != format!("{kind}: {message}") {
+            table += &format!("\n{} day {} {kind}:\n{message}\n", report.year, report.day);
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registry::answers;
+    use anyhow::bail;
+    use std::thread;
+
+    fn solve_fast(_: &str) -> Result<Answers> {
+        Ok(answers(1, "line\nline"))
+    }
+
+    fn solve_slow(_: &str) -> Result<Answers> {
+        thread::sleep(Duration::from_millis(50));
+        Ok(answers(2, 3))
+    }
+
+    fn solve_broken(_: &str) -> Result<Answers> {
+        bail!("bad input")
+    }
+
+    fn solve_panicking(_: &str) -> Result<Answers> {
+        panic!("not finished")
+    }
+
+    #[test]
+    fn test_run_all() -> Result<()> {
+        let solutions = [
+            Solution::new(2022, 1, solve_fast),
+            Solution::new(2022, 2, solve_panicking),
+            Solution::new(2022, 3, solve_slow),
+            Solution::new(2022, 4, solve_broken),
+        ];
+        let reports = run_all(&solutions, Some(2), |solution| (solution.solve)(""))?;
+
+        assert_eq!(reports.len(), 4);
+        assert!(reports
+            .iter()
+            .tuple_windows()
+            .all(|(slower, faster)| slower.elapsed >= faster.elapsed));
+        let report = |day| reports.iter().find(|report| report.day == day).unwrap();
+        assert_eq!(report(3).status, Status::Solved(answers(2, 3)));
+        assert!(report(3).elapsed >= Duration::from_millis(50));
+        assert_eq!(
+            report(2).status,
+            Status::Panicked("not finished".to_string())
+        );
+        assert_eq!(report(4).status, Status::Failed("bad input".to_string()));
+
+        let summary = summary(&reports);
+        let lines = summary.lines().collect_vec();
+        assert!(lines[0].starts_with("Year  Day  Time"), "{summary}");
+        let row = |day: &str| {
+            let row = lines
+                .iter()
+                .find(|line| line.starts_with(&format!("2022  {day} ")));
+            row.unwrap().split_whitespace().skip(3).join(" ")
+        };
+        assert_eq!(row("3"), "ok 2 3");
+        assert_eq!(row("1"), "ok 1 (2 lines)");
+        assert_eq!(row("4"), "error: bad input");
+        assert!(summary.contains("panic: not finished"), "{summary}");
+        assert!(lines[5].starts_with("4 days, 2 failed, "), "{summary}");
+        Ok(())
+    }
+
+    #[test]
+    fn test_summary_of_long_messages() {
+        let report = |day, status| Report {
+            year: 2022,
+            day,
+            status,
+            elapsed: Duration::from_millis(1),
+        };
+        let long = format!("internal error: {}", "x".repeat(1500));
+        let reports = [
+            report(11, Status::Panicked(long.clone())),
+            report(4, Status::Failed("line 2: bad\n  |\n2 | 6-x8".to_string())),
+        ];
+        let summary = summary(&reports);
+        let lines = summary.lines().collect_vec();
+        assert!(lines[..3].iter().all(|line| line.len() < 100), "{summary}");
+        let status = lines[1].split("  ").last().unwrap();
+        assert!(
+            status.starts_with("panic: internal error: xxx"),
+            "{summary}"
+        );
+        assert_eq!(status.chars().count(), STATUS_WIDTH);
+        assert!(status.ends_with("x..."), "{summary}");
+        assert!(lines[2].ends_with("error: line 2: bad ..."), "{summary}");
+        assert_eq!(lines[5..7], ["2022 day 11 panic:", long.as_str()]);
+        assert!(summary.ends_with("2022 day 4 error:\nline 2: bad\n  |\n2 | 6-x8\n"));
+    }
+}
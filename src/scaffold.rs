@@ -0,0 +1,332 @@
+use anyhow::{bail, ensure, Context, Result};
+use itertools::Itertools;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MODULE_TEMPLATE: &str = r#"use crate::registry::{answers, Answers};
+use anyhow::Result;
+use itertools::Itertools;
+
+type PuzzleResult = usize;
+
+pub fn solve(input: &str) -> Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
+}
+
+fn inner(input: &str) -> Result<(PuzzleResult, PuzzleResult)> {
+    let _lines = parse(input)?;
+    let part1 = PuzzleResult::default();
+    let part2 = PuzzleResult::default();
+    Ok((part1, part2))
+}
+
+fn parse(input: &str) -> Result<Vec<&str>> {
+    Ok(input.lines().collect_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    crate::examples::example_tests!({year}, {day});
+}
+"#;
+
+const GRAMMAR_MODULE_TEMPLATE: &str = r#"use crate::grammars;
+use crate::parsing::ParseError;
+use crate::registry::{answers, Answers};
+use anyhow::Result;
+use itertools::Itertools;
+use pest::Parser;
+use pest_derive::Parser;
+
+type PuzzleResult = usize;
+
+pub fn solve(input: &str) -> Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
+}
+
+fn inner(input: &str) -> Result<(PuzzleResult, PuzzleResult)> {
+    let _lines = parse(input)?;
+    let part1 = PuzzleResult::default();
+    let part2 = PuzzleResult::default();
+    Ok((part1, part2))
+}
+
+#[derive(Parser)]
+#[grammar = "solutions{year}/day{day}.pest"]
+struct InputParser;
+
+pub(crate) fn parse_tree(input: &str) -> Result<String, ParseError> {
+    grammars::parse_tree::<InputParser, _>(Rule::Grammar, input)
+}
+
+fn parse(input: &str) -> Result<Vec<&str>> {
+    let pairs = InputParser::parse(Rule::Grammar, input)?;
+    Ok(pairs
+        .filter(|pair| pair.as_rule() == Rule::line)
+        .map(|pair| pair.as_str())
+        .collect_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    crate::examples::example_tests!({year}, {day});
+}
+"#;
+
+const GRAMMAR_TEMPLATE: &str = include_str!("scaffold.pest");
+
+/// Parse tree of the empty example with [GRAMMAR_TEMPLATE], snapshotted with the example so that
+/// the grammar tests pass until the example is filled in.
+const EMPTY_EXAMPLE_TREE: &str = "EOI \"\"\n";
+
+fn render(template: &str, year: u32, day: u32) -> String {
+    template
+        .replace("{year}", &year.to_string())
+        .replace("{day}", &day.to_string())
+}
+
+/// Number of the day in lines like `pub(crate) mod day7;` and
+/// `Solution::new(2023, 7, day7::solve),`.
+fn day_of(line: &str, prefix: &str) -> Option<u32> {
+    let rest = line.trim().strip_prefix(prefix)?;
+    let digits = rest
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect::<String>();
+    digits.parse().ok()
+}
+
+/// Inserts `new_line` among the lines starting with `prefix`, keeping them sorted by day.
+fn insert_sorted(lines: &mut Vec<String>, prefix: &str, day: u32, new_line: String) -> Result<()> {
+    let existing = lines
+        .iter()
+        .enumerate()
+        .filter_map(|(i, line)| day_of(line, prefix).map(|existing_day| (i, existing_day)))
+        .collect_vec();
+    ensure!(
+        existing
+            .iter()
+            .all(|(_, existing_day)| *existing_day != day),
+        "Day {day} is already registered"
+    );
+    let previous = existing
+        .iter()
+        .rfind(|(_, existing_day)| *existing_day < day);
+    let index = match (previous, existing.first()) {
+        (Some((i, _)), _) => i + 1,
+        (None, Some((first, _))) => *first,
+        (None, None) => bail!("No '{prefix}' lines to add day {day} to"),
+    };
+    lines.insert(index, new_line);
+    Ok(())
+}
+
+/// Adds the `mod` declaration and the registry entry of a new day to a year's `mod.rs`, and the
+/// entry of its grammar when it has one. The first grammar of a year adds the `grammars` function.
+fn register_day(mod_rs: &str, year: u32, day: u32, grammar: bool) -> Result<String> {
+    let mut lines = mod_rs.lines().map(String::from).collect_vec();
+    insert_sorted(
+        &mut lines,
+        "pub(crate) mod day",
+        day,
+        format!("pub(crate) mod day{day};"),
+    )?;
+    insert_sorted(
+        &mut lines,
+        &format!("Solution::new({year}, "),
+        day,
+        format!("        Solution::new({year}, {day}, day{day}::solve),"),
+    )?;
+    if grammar {
+        let prefix = format!("Grammar::new({year}, ");
+        let entry = format!("        {prefix}{day}, day{day}::parse_tree),");
+        if lines.iter().any(|line| line.trim().starts_with(&prefix)) {
+            insert_sorted(&mut lines, &prefix, day, entry)?;
+        } else {
+            lines.insert(0, "use crate::grammars::Grammar;".to_string());
+            lines.extend([
+                String::new(),
+                "pub(crate) fn grammars() -> Vec<Grammar> {".to_string(),
+                "    vec![".to_string(),
+                entry,
+                "    ]".to_string(),
+                "}".to_string(),
+            ]);
+        }
+    }
+    Ok(lines.join("\n") + "\n")
+}
+
+fn create(path: &Path, contents: &str, created: &mut Vec<PathBuf>) -> Result<()> {
+    if path.exists() {
+        return Ok(());
+    }
+    fs::write(path, contents).with_context(|| format!("Can't create {}", path.display()))?;
+    created.push(path.to_path_buf());
+    Ok(())
+}
+
+/// Generates the module of a new day under `root`, registers it, and creates its empty input,
+/// example and answers files, plus the parse tree snapshot of the example for a grammar. Returns
+/// every file created or modified.
+pub fn new_day(root: &Path, year: u32, day: u32, grammar: bool) -> Result<Vec<PathBuf>> {
+    ensure!(
+        (1..=25).contains(&day),
+        "Day must be between 1 and 25, got {day}"
+    );
+    let solutions_dir = root.join(format!("src/solutions{year}"));
+    let mod_rs = solutions_dir.join("mod.rs");
+    ensure!(
+        mod_rs.exists(),
+        "No solutions for {year}: {} doesn't exist",
+        mod_rs.display()
+    );
+    let module = solutions_dir.join(format!("day{day}.rs"));
+    ensure!(!module.exists(), "{} already exists", module.display());
+
+    let registered = register_day(&fs::read_to_string(&mod_rs)?, year, day, grammar)
+        .with_context(|| format!("Can't register day {day} in {}", mod_rs.display()))?;
+
+    let mut created = vec![];
+    let template = if grammar {
+        create(
+            &solutions_dir.join(format!("day{day}.pest")),
+            GRAMMAR_TEMPLATE,
+            &mut created,
+        )?;
+        GRAMMAR_MODULE_TEMPLATE
+    } else {
+        MODULE_TEMPLATE
+    };
+    create(&module, &render(template, year, day), &mut created)?;
+    fs::write(&mod_rs, registered)?;
+    created.push(mod_rs);
+
+    let inputs_dir = root.join(format!("inputs/{year}"));
+    fs::create_dir_all(&inputs_dir)?;
+    for file in ["txt", "example.txt", "example.answers.txt"] {
+        create(
+            &inputs_dir.join(format!("day{day}.{file}")),
+            "",
+            &mut created,
+        )?;
+    }
+    if grammar {
+        create(
+            &inputs_dir.join(format!("day{day}.example.tree.txt")),
+            EMPTY_EXAMPLE_TREE,
+            &mut created,
+        )?;
+    }
+    Ok(created)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::strings::dedent;
+
+    #[cfg(feature = "grammar")]
+    #[derive(pest_derive::Parser)]
+    #[grammar = "scaffold.pest"]
+    struct TemplateParser;
+
+    fn mod_rs() -> String {
+        dedent(
+            "
+            use crate::registry::Solution;
+
+            pub(crate) mod day1;
+            pub(crate) mod day3;
+
+            pub(crate) fn solutions() -> Vec<Solution> {
+                vec![
+                    Solution::new(2023, 1, day1::solve),
+                    Solution::new(2023, 3, day3::solve),
+                ]
+            }
+            ",
+        )
+    }
+
+    #[test]
+    fn test_register_day() -> Result<()> {
+        let registered = register_day(&mod_rs(), 2023, 2, false)?;
+        assert!(registered
+            .contains("pub(crate) mod day1;\npub(crate) mod day2;\npub(crate) mod day3;\n"));
+        assert!(registered.contains(
+            "Solution::new(2023, 2, day2::solve),\n        Solution::new(2023, 3, day3::solve),"
+        ));
+
+        let registered = register_day(&mod_rs(), 2023, 10, false)?;
+        assert!(registered.contains("pub(crate) mod day3;\npub(crate) mod day10;\n"));
+        assert!(registered.contains("Solution::new(2023, 10, day10::solve),\n    ]"));
+        assert!(!registered.contains("Grammar"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_grammar() -> Result<()> {
+        let registered = register_day(&mod_rs(), 2023, 4, true)?;
+        assert!(registered.starts_with("use crate::grammars::Grammar;\n"));
+        assert!(registered.ends_with(
+            "}\n\npub(crate) fn grammars() -> Vec<Grammar> {\n    vec![\n        \
+             Grammar::new(2023, 4, day4::parse_tree),\n    ]\n}\n"
+        ));
+
+        let registered = register_day(&registered, 2023, 2, true)?;
+        assert!(registered.contains(
+            "Grammar::new(2023, 2, day2::parse_tree),\n        \
+             Grammar::new(2023, 4, day4::parse_tree),"
+        ));
+        assert_eq!(
+            registered.matches("use crate::grammars::Grammar;").count(),
+            1
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_register_existing_day() {
+        let error = register_day(&mod_rs(), 2023, 3, false).unwrap_err();
+        assert_eq!(error.to_string(), "Day 3 is already registered");
+    }
+
+    #[test]
+    fn test_new_day() -> Result<()> {
+        let root = std::env::temp_dir().join(format!("scaffold-{}", std::process::id()));
+        fs::create_dir_all(root.join("src/solutions2023"))?;
+        fs::write(root.join("src/solutions2023/mod.rs"), mod_rs())?;
+
+        let created = new_day(&root, 2023, 2, true)?;
+        let module = fs::read_to_string(root.join("src/solutions2023/day2.rs"))?;
+        assert!(module.contains("#[grammar = \"solutions2023/day2.pest\"]"));
+        assert!(module.contains("crate::examples::example_tests!(2023, 2);"));
+        let mod_rs = fs::read_to_string(root.join("src/solutions2023/mod.rs"))?;
+        assert!(mod_rs.contains("Grammar::new(2023, 2, day2::parse_tree),"));
+        assert!(root.join("src/solutions2023/day2.pest").exists());
+        assert!(root.join("inputs/2023/day2.example.txt").exists());
+        assert_eq!(created.len(), 7);
+        // Like the grammar tests, the tree of the example must match its snapshot
+        #[cfg(feature = "grammar")]
+        {
+            let example = root.join("inputs/2023/day2.example.txt");
+            let tree = crate::grammars::parse_tree::<TemplateParser, _>(
+                Rule::Grammar,
+                &fs::read_to_string(&example)?,
+            )?;
+            let snapshot = crate::grammars::snapshot_path(&example);
+            assert_eq!(tree, fs::read_to_string(snapshot)?);
+        }
+
+        let error = new_day(&root, 2023, 2, false).unwrap_err();
+        assert!(
+            error.to_string().ends_with("day2.rs already exists"),
+            "{error}"
+        );
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -1,29 +1,29 @@
-use crate::files::lines;
-use itertools::Itertools;
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use crate::strings::sections;
+use anyhow::Context;
 
-pub fn parse_elves_calories() -> Vec<i32> {
-    let lines = lines("inputs/2022/day1.txt");
-
-    let mut elves_calories = Vec::new();
-    for (_key, group) in &lines.into_iter().group_by(|line| !line.is_empty()) {
-        let c: i32 = group
-            .take_while(|line| !line.is_empty())
-            .map(|line| line.parse::<i32>().unwrap())
-            .sum();
-        elves_calories.push(c);
-    }
-    return elves_calories;
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let mut calories = parse(input)?;
+    let part1 = *calories.iter().max().context("No elves")?;
+    calories.sort();
+    let part2: i32 = calories.iter().rev().take(3).sum();
+    Ok(answers(part1, part2))
 }
 
-pub fn part1() -> i32 {
-    parse_elves_calories()
-        .into_iter()
-        .max()
-        .expect("day1.txt file is empty")
+fn parse(input: &str) -> Result<Vec<i32>, ParseError> {
+    sections(input)
+        .map(|elf| {
+            elf.lines()
+                .iter()
+                .enumerate()
+                .map(|(i, line)| number::<i32>(line, line).on_line(elf.start + i + 1, line))
+                .sum()
+        })
+        .collect()
 }
 
-pub fn part2() -> i32 {
-    let mut calories = parse_elves_calories();
-    calories.sort();
-    return calories.iter().rev().take(3).sum();
+#[cfg(test)]
+mod tests {
+    crate::examples::example_tests!(2022, 1);
 }
@@ -1,10 +1,11 @@
 use crate::containers::split_into_slices;
-use crate::files::lines;
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use crate::strings::to_lines;
 use itertools::Itertools;
 use ndarray::prelude::*;
 use std::fmt::{Debug, Display, Write};
 use std::iter::zip;
-use std::num::ParseIntError;
 use Instruction::*;
 
 type PuzzleResult = i32;
@@ -13,9 +14,9 @@ type PuzzleResultPart2 = String;
 #[derive(Eq, Hash, Ord, PartialOrd, PartialEq, Debug, Copy, Clone)]
 pub struct Position(pub i32, pub i32);
 
-pub fn day10() -> (PuzzleResult, PuzzleResultPart2) {
-    let lines_of_file = lines("inputs/2022/day10.txt");
-    inner(lines_of_file)
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let (part1, part2) = inner(to_lines(input))?;
+    Ok(answers(part1, part2))
 }
 
 #[derive(Eq, PartialEq, Debug, Copy, Clone)]
@@ -24,27 +25,28 @@ enum Instruction {
     AddX(i32),
 }
 
-fn parse(lines: Vec<String>) -> Result<Vec<Instruction>, String> {
-    let ret = lines
+fn parse_instruction(line: &str) -> Result<Instruction, ParseError> {
+    let chars = line.split_ascii_whitespace().collect_vec();
+    match chars.as_slice() {
+        ["noop"] => Ok(Noop),
+        ["addx", num] => Ok(AddX(number(line, num)?)),
+        ["noop" | "addx", ..] => Err(ParseError::new("wrong number of arguments")),
+        [name, ..] => Err(ParseError::new(format!("unknown instruction '{name}'")).at(line, name)),
+        [] => Err(ParseError::new("expected an instruction")),
+    }
+}
+
+fn parse(lines: Vec<String>) -> Result<Vec<Instruction>, ParseError> {
+    lines
         .iter()
-        .map(|line| {
-            let chars = line.split_ascii_whitespace().collect_vec();
-            let slice = chars.as_slice();
-            match slice {
-                ["noop"] => Ok(Noop),
-                ["addx", num] => {
-                    let num = num.parse().map_err(|err: ParseIntError| err.to_string())?;
-                    Ok(AddX(num))
-                }
-                _ => Err(format!("Can't parse line: {}", line)),
-            }
-        })
-        .try_collect()?;
-    Ok(ret)
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_instruction(line).on_line(i + 1, line))
+        .collect()
 }
 
-fn inner(lines: Vec<String>) -> (PuzzleResult, PuzzleResultPart2) {
-    let instructions = parse(lines).unwrap();
+fn inner(lines: Vec<String>) -> Result<(PuzzleResult, PuzzleResultPart2), ParseError> {
+    let instructions = parse(lines)?;
 
     let part1 = part1(&instructions);
 
@@ -52,7 +54,7 @@ fn inner(lines: Vec<String>) -> (PuzzleResult, PuzzleResultPart2) {
     let x_values = simulate(&instructions, part2_cycles);
     let part2 = render(x_values.iter());
 
-    (part1, part2)
+    Ok((part1, part2))
 }
 
 fn part1(instructions: &Vec<Instruction>) -> PuzzleResult {
@@ -140,188 +142,17 @@ fn simulate<const N: usize>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::examples::example_input;
     use crate::strings::SkipEmptyLines;
     use itertools::Itertools;
     use nofmt;
     use pretty_assertions::assert_eq as pretty_assert_eq;
     use textwrap::dedent;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    fn big_program() -> String {
-        dedent(
-            "
-        addx 15
-        addx -11
-        addx 6
-        addx -3
-        addx 5
-        addx -1
-        addx -8
-        addx 13
-        addx 4
-        noop
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx 5
-        addx -1
-        addx -35
-        addx 1
-        addx 24
-        addx -19
-        addx 1
-        addx 16
-        addx -11
-        noop
-        noop
-        addx 21
-        addx -15
-        noop
-        noop
-        addx -3
-        addx 9
-        addx 1
-        addx -3
-        addx 8
-        addx 1
-        addx 5
-        noop
-        noop
-        noop
-        noop
-        noop
-        addx -36
-        noop
-        addx 1
-        addx 7
-        noop
-        noop
-        noop
-        addx 2
-        addx 6
-        noop
-        noop
-        noop
-        noop
-        noop
-        addx 1
-        noop
-        noop
-        addx 7
-        addx 1
-        noop
-        addx -13
-        addx 13
-        addx 7
-        noop
-        addx 1
-        addx -33
-        noop
-        noop
-        noop
-        addx 2
-        noop
-        noop
-        noop
-        addx 8
-        noop
-        addx -1
-        addx 2
-        addx 1
-        noop
-        addx 17
-        addx -9
-        addx 1
-        addx 1
-        addx -3
-        addx 11
-        noop
-        noop
-        addx 1
-        noop
-        addx 1
-        noop
-        noop
-        addx -13
-        addx -19
-        addx 1
-        addx 3
-        addx 26
-        addx -30
-        addx 12
-        addx -1
-        addx 3
-        addx 1
-        noop
-        noop
-        noop
-        addx -9
-        addx 18
-        addx 1
-        addx 2
-        noop
-        noop
-        addx 9
-        noop
-        noop
-        noop
-        addx -1
-        addx 2
-        addx -37
-        addx 1
-        addx 3
-        noop
-        addx 15
-        addx -21
-        addx 22
-        addx -6
-        addx 1
-        noop
-        addx 2
-        addx 1
-        noop
-        addx -10
-        noop
-        noop
-        addx 20
-        addx 1
-        addx 2
-        addx 2
-        addx -6
-        addx -11
-        noop
-        noop
-        noop",
-        )
-        .skip_empty_start_lines()
-    }
-    #[test]
-    fn test_part1_inner() -> Result<(), String> {
-        let input_string = big_program();
-        let actual = inner(to_vec(input_string.lines().collect_vec())).0;
-        assert_eq!(actual, 13140);
-
-        Ok(())
-    }
+    crate::examples::example_tests!(2022, 10);
 
     #[test]
-    fn test_part2_inner() -> Result<(), String> {
-        let input_string = big_program();
-        let actual = inner(to_vec(input_string.lines().collect_vec())).1;
-        assert_ne!(actual, "");
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_parse() -> Result<(), String> {
+    fn test_parse() -> Result<(), ParseError> {
         let input_string = dedent(
             "
             noop
@@ -329,7 +160,7 @@ mod tests {
             addx -5",
         )
         .skip_empty_start_lines();
-        let lines = to_vec(input_string.lines().collect_vec());
+        let lines = to_lines(&input_string);
         let instructions = parse(lines)?;
         assert_eq!(
             instructions,
@@ -342,6 +173,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let error = parse(to_lines("noop\naddx\nnoop")).unwrap_err();
+        assert_eq!(error.to_string(), "line 2: wrong number of arguments\n  |\n2 | addx");
+        let error = parse(to_lines("noop\nmulx 3")).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(1)));
+        let error = parse(to_lines("addx 1x")).unwrap_err();
+        assert_eq!(error.message, "invalid number '1x': invalid digit found in string");
+        assert_eq!(error.column, Some(6));
+    }
+
     #[test]
     fn test_simulate() -> Result<(), String> {
         let instructions = vec![
@@ -395,8 +237,8 @@ mod tests {
     }
 
     #[test]
-    fn test_simulate_long_instructions() -> Result<(), String> {
-        let lines = to_vec(big_program().lines().collect_vec());
+    fn test_simulate_long_instructions() -> Result<(), ParseError> {
+        let lines = to_lines(&example_input(2022, 10));
         let instructions = parse(lines)?;
         let vals = simulate(&instructions, &[20, 60, 100, 140, 180, 220]);
         // let vals = simulate(&instructions, &[180, 220]);
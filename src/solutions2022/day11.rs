@@ -1,6 +1,9 @@
-use crate::files::{contents, lines};
+use crate::grammars;
+use crate::parsing::{ParseError, ParseResult};
+use crate::registry::{answers, Answers};
 use derive_builder::Builder;
 use itertools::Itertools;
+use log::{debug, trace};
 use ndarray::prelude::*;
 use std::fmt::{Debug, Display, Write};
 use pest::Parser;
@@ -12,8 +15,9 @@ type PuzzleResult = i32;
 #[derive(Eq, Hash, Ord, PartialOrd, PartialEq, Debug, Copy, Clone)]
 pub struct Position(pub i32, pub i32);
 
-pub fn day11() -> (PuzzleResult, PuzzleResult) {
-    inner(&contents("inputs/2022/day11.txt"))
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
 }
 
 #[derive(Builder, Default)]
@@ -25,156 +29,88 @@ struct Monkey {
 #[grammar = "solutions2022/day11.pest"]
 struct MonkeyParser;
 
-fn parse(input: &String) -> anyhow::Result<Vec<Monkey>> {
+pub(crate) fn parse_tree(input: &str) -> Result<String, ParseError> {
+    grammars::parse_tree::<MonkeyParser, _>(Rule::Grammar, input)
+}
+
+fn parse(input: &str) -> Result<Vec<Monkey>, ParseError> {
     let mut ret: Vec<Monkey> = Vec::new();
     let monkeys = MonkeyParser::parse(Rule::Grammar, &input)?;
-    println!("{}", monkeys.to_json());
+    trace!("{}", grammars::tree(monkeys.clone()));
     for monkey in monkeys {
-        println!("Parsing monkey!\n----------");
+        debug!("Parsing monkey");
         match monkey.as_rule() {
             Rule::Monkey => {
-                let monkey = build_monkey(monkey);
-                // let mut inner_rules = monkey.into_inner(); // { name }
-                // let foo = inner_rules.next().unwrap();
-                // println!("{:?}", foo);
-                // monkey_builder.monkey_id()
+                let monkey = build_monkey(monkey)?;
             },
             Rule::EOI => (),
-            _ => {
-                let mut inner_rules = monkey.into_inner(); // { name }
-                let foo = inner_rules.next().unwrap();
-                println!("{:?}", foo);
-                unreachable!()
+            rule => {
+                let error = ParseError::new(format!("unexpected {rule:?} at the top of the input"));
+                return Err(error.at_span(monkey.as_span()));
             },
         }
-        println!("=================> monkey parsed");
+        debug!("Monkey parsed");
     }
     Ok(ret)
 }
 
-fn build_monkey(monkey_pair: pest::iterators::Pair<Rule>) -> Monkey {
+fn build_monkey(monkey_pair: pest::iterators::Pair<Rule>) -> Result<Monkey, ParseError> {
     let mut monkey_builder = MonkeyBuilder::default();
-    println!("monkey_pair: {:?}", monkey_pair);
+    trace!("monkey_pair: {:?}", monkey_pair);
     let monkey_pairs = monkey_pair.into_inner();
-    println!("monkey_pairs: {:?}", &monkey_pairs);
+    trace!("monkey_pairs: {:?}", &monkey_pairs);
     for pair in monkey_pairs {
         match pair.as_rule() {
             Rule::monkey_id => {
                 let mut monkey_id_pair = pair.as_str();
-                println!("Rule::monkey_id -> {:?}", monkey_id_pair);
-                // let id = monkey_id_pair[0].as_str();
-                // println!("Rule::monkey_id -> {:?}", id);
+                debug!("Rule::monkey_id -> {:?}", monkey_id_pair);
             },
             Rule::items => {
                 let items = pair.as_str()
                     .split(",")
                     .map(|s| s.trim().parse::<usize>())
                     .try_collect()
-                    .unwrap();
+                    .at_span(pair.as_span())?;
                 monkey_builder.items(items);
             },
             Rule::operation => {
-                println!("Rule::operation -> {:?}", pair);
-                println!("Rule::operation -> {:?}", pair.into_inner());
+                debug!("Rule::operation -> {:?}", pair);
+                trace!("Rule::operation -> {:?}", pair.into_inner());
+            },
+            Rule::test | Rule::test_true_monkey_id | Rule::test_false_monkey_id => {
+                debug!("{:?} -> {:?}", pair.as_rule(), pair.as_str());
             },
-            _ => {
-                println!("unreachable -> {:?}", pair);
-                let foo = pair.into_inner().collect_vec();
-                println!("unreachable -> {:?}", foo);
-                // unreachable!()
+            rule => {
+                let error = ParseError::new(format!("unexpected {rule:?} in a monkey"));
+                return Err(error.at_span(pair.as_span()));
             },
         };
     }
-    Monkey::default()
+    Ok(Monkey::default())
     // monkey_builder.build().unwrap()
 }
 
-fn inner(input: &String) -> (PuzzleResult, PuzzleResult) {
-    let instructions = parse(&input).unwrap();
+fn inner(input: &str) -> Result<(PuzzleResult, PuzzleResult), ParseError> {
+    let instructions = parse(&input)?;
 
     let part1 = PuzzleResult::default();
     let part2 = PuzzleResult::default();
 
-    (part1, part2)
+    Ok((part1, part2))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::strings::SkipEmptyLines;
-    use itertools::Itertools;
+    use crate::examples::example_input;
     use nofmt;
     use pretty_assertions::assert_eq as pretty_assert_eq;
-    use textwrap::dedent;
-
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    fn program() -> String {
-        return dedent(
-            "
-            Monkey 0:
-              Starting items: 79, 98
-              Operation: new = old * 19
-              Test: divisible by 23
-                If true: throw to monkey 2
-                If false: throw to monkey 3
-            ")
-            .skip_empty_start_lines();
-
-        return dedent(
-            "
-            Monkey 0:
-              Starting items: 79, 98
-              Operation: new = old * 19
-              Test: divisible by 23
-                If true: throw to monkey 2
-                If false: throw to monkey 3
-
-            Monkey 1:
-              Starting items: 54, 65, 75, 74
-              Operation: new = old + 6
-              Test: divisible by 19
-                If true: throw to monkey 2
-                If false: throw to monkey 0
-
-            Monkey 2:
-              Starting items: 79, 60, 97
-              Operation: new = old * old
-              Test: divisible by 13
-                If true: throw to monkey 1
-                If false: throw to monkey 3
 
-            Monkey 3:
-              Starting items: 74
-              Operation: new = old + 3
-              Test: divisible by 17
-                If true: throw to monkey 0
-                If false: throw to monkey 1",
-        )
-        .skip_empty_start_lines();
-    }
-    #[test]
-    fn test_part1_inner() -> Result<(), String> {
-        let input_string = program();
-        let actual = inner(&input_string).0;
-        assert_eq!(actual, 10605);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_inner() -> Result<(), String> {
-        let input_string = program();
-        let actual = inner(&input_string).1;
-        assert_eq!(actual, 0);
-        Ok(())
-    }
+    crate::examples::example_tests!(2022, 11, ignore = "day 11 is not solved yet");
 
     #[test]
-    fn test_parse() -> anyhow::Result<()> {
-        let input_string = program();
+    fn test_parse() -> Result<(), ParseError> {
+        let input_string = example_input(2022, 11);
         let monkeys = parse(&input_string)?;
         // assert_eq!(monkeys, vec![MonkeyBuilder::new()]);
         Ok(())
@@ -1,4 +1,5 @@
-use crate::files::lines;
+use crate::parsing::{parse_lines, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
 use PossibilityOrDesiredResult::*;
 
 #[derive(Copy, Clone)]
@@ -29,21 +30,26 @@ struct Game {
     player2: Play,
 }
 
-fn parse_line(line: &str, parse_desired_result: bool) -> PartialGame {
-    let (player1, player2) = line.split_at(1);
-    let player1_play = match parse_possibility(player1.trim(), false) {
+fn parse_line(line: &str, parse_desired_result: bool) -> Result<PartialGame, ParseError> {
+    let (player1, player2) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new("expected two plays separated by a space"))?;
+    let player1_play = match parse_possibility(player1, false).at(line, player1)? {
         PossibilityOrDesiredResult::Possibility(play) => play,
-        _ => panic!("First play must always be a play!"),
+        _ => unreachable!("First play must always be a play!"),
     };
 
-    return PartialGame {
+    Ok(PartialGame {
         player1: player1_play,
-        player2: parse_possibility(player2.trim(), parse_desired_result),
-    };
+        player2: parse_possibility(player2, parse_desired_result).at(line, player2)?,
+    })
 }
 
-fn parse_possibility(raw_play: &str, parse_desired_result: bool) -> PossibilityOrDesiredResult {
-    if parse_desired_result {
+fn parse_possibility(
+    raw_play: &str,
+    parse_desired_result: bool,
+) -> Result<PossibilityOrDesiredResult, ParseError> {
+    let possibility = if parse_desired_result {
         match raw_play {
             "A" => Possibility(Play::Rock),
             "B" => Possibility(Play::Paper),
@@ -51,30 +57,30 @@ fn parse_possibility(raw_play: &str, parse_desired_result: bool) -> PossibilityO
             "X" => PossibilityOrDesiredResult::DesiredResult(MatchResult::Loss),
             "Y" => PossibilityOrDesiredResult::DesiredResult(MatchResult::Draw),
             "Z" => PossibilityOrDesiredResult::DesiredResult(MatchResult::Win),
-            _ => panic!("Unknown play: {raw_play}"),
+            _ => return Err(ParseError::new(format!("unknown play '{raw_play}'"))),
         }
     } else {
         match raw_play {
             "A" | "X" => Possibility(Play::Rock),
             "B" | "Y" => Possibility(Play::Paper),
             "C" | "Z" => Possibility(Play::Scissors),
-            _ => panic!("Unknown play: {raw_play}"),
+            _ => return Err(ParseError::new(format!("unknown play '{raw_play}'"))),
         }
-    }
+    };
+    Ok(possibility)
 }
 
-pub fn day2part1() -> i32 {
-    let lines_of_file = lines("inputs/2022/day2.txt");
-    day2part1_inner(lines_of_file)
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    Ok(answers(day2part1_inner(input)?, day2part2_inner(input)?))
 }
 
-fn day2part1_inner(l: Vec<String>) -> i32 {
-    l.iter()
-        .filter(|line| line.len() == 3)
-        .map(|line| parse_line(line, false))
+fn day2part1_inner(input: &str) -> Result<i32, ParseError> {
+    let games = parse_lines(input, |line| parse_line(line, false))?;
+    Ok(games
+        .into_iter()
         .map(|game| convert_to_game_as_day1(game))
         .map(|game| calculate_game_score(game))
-        .sum()
+        .sum())
 }
 
 fn convert_to_game_as_day1(game: PartialGame) -> Game {
@@ -90,20 +96,26 @@ fn convert_to_game_as_day1(game: PartialGame) -> Game {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use itertools::Itertools;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
+    crate::examples::example_tests!(2022, 2);
 
     #[test]
     fn test_day2part1_inner() {
-        assert_eq!(day2part1_inner(to_vec(vec!["A Y"])), 8);
+        assert_eq!(day2part1_inner("A Y"), Ok(8));
     }
 
     #[test]
     fn test_day2part2_inner() {
-        assert_eq!(day2part2_inner(to_vec(vec!["A Y"])), 4);
+        assert_eq!(day2part2_inner("A Y"), Ok(4));
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = day2part2_inner("A Y\nB W").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: unknown play 'W'\n  |\n2 | B W\n  |   ^"
+        );
     }
 }
 
@@ -130,11 +142,6 @@ fn calculate_game_score(game: Game) -> i32 {
     return match_score + play_score;
 }
 
-pub fn day2part2() -> i32 {
-    let lines_of_file = lines("inputs/2022/day2.txt");
-    day2part2_inner(lines_of_file)
-}
-
 fn play_game(game: PartialGame) -> Game {
     let player2 = match game.player2 {
         Possibility(play) => play,
@@ -158,11 +165,11 @@ fn play_game(game: PartialGame) -> Game {
     }
 }
 
-fn day2part2_inner(l: Vec<String>) -> i32 {
-    l.iter()
-        .filter(|line| line.len() == 3)
-        .map(|line| parse_line(line, true))
+fn day2part2_inner(input: &str) -> Result<i32, ParseError> {
+    let games = parse_lines(input, |line| parse_line(line, true))?;
+    Ok(games
+        .into_iter()
         .map(|game| play_game(game))
         .map(|game| calculate_game_score(game))
-        .sum()
+        .sum())
 }
@@ -1,15 +1,12 @@
-use crate::files::lines;
+use crate::containers::chunk_into_groups;
+use crate::parsing::{parse_lines, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
 use crate::strings;
+use crate::strings::CommonCharError;
 use itertools::Itertools;
 
-pub fn day3part1() -> i32 {
-    let lines_of_file = lines("inputs/2022/day3.txt");
-    day3part1_inner(lines_of_file)
-}
-
-pub fn day3part2() -> i32 {
-    let lines_of_file = lines("inputs/2022/day3.txt");
-    day3part2_inner(lines_of_file)
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    Ok(answers(day3part1_inner(input)?, day3part2_inner(input)?))
 }
 
 struct Rucksack {
@@ -17,85 +14,84 @@ struct Rucksack {
 }
 
 fn calculate_priority(rucksack: Rucksack) -> i32 {
-    let c = rucksack.common;
-    assert!(rucksack.common.is_ascii());
-    let value = if rucksack.common.is_lowercase() {
-        (c as u32) - ('a' as u32) + 1
-    } else {
-        (c as u32) - ('A' as u32) + 27
-    };
-    value as i32
+    let index = strings::letter_index(rucksack.common)
+        .unwrap_or_else(|| panic!("Invalid item: {}", rucksack.common));
+    index as i32 + 1
 }
 
-fn parse_line(line: &str) -> Rucksack {
+fn parse_line(line: &str) -> Result<Rucksack, CommonCharError> {
     let (left, right) = line.split_at(line.len() / 2);
-    let common = strings::common_char(&vec![left, right]);
+    let common = strings::common_char(&[left, right])?;
 
-    Rucksack { common }
+    Ok(Rucksack { common })
 }
 
-fn day3part1_inner(lines: Vec<String>) -> i32 {
-    lines
-        .iter()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| parse_line(line))
-        .map(|rucksack| calculate_priority(rucksack))
-        .sum()
+fn day3part1_inner(input: &str) -> Result<i32, ParseError> {
+    let rucksacks = parse_lines(input, |line| {
+        parse_line(line).map_err(|err| ParseError::new(format!("invalid rucksack: {err}")))
+    })?;
+    Ok(rucksacks.into_iter().map(calculate_priority).sum())
 }
 
-fn day3part2_inner(lines: Vec<String>) -> i32 {
-    lines
-        .iter()
-        .filter(|line| !line.trim().is_empty())
-        .chunks(3)
+fn day3part2_inner(input: &str) -> Result<i32, ParseError> {
+    let lines = input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .collect_vec();
+    let groups = chunk_into_groups::<_, 3>(&lines)
+        .map_err(|err| ParseError::new(format!("Elves must come in groups of three: {err}")))?;
+    let rucksacks: Vec<_> = groups
         .into_iter()
-        .map(|chunk| parse_chunk(chunk.collect_vec()))
-        .map(|rucksack| calculate_priority(rucksack))
-        .sum()
+        .map(|&[(i, first), (_, second), (_, third)]| {
+            parse_chunk(&[first, second, third])
+                .map_err(|err| ParseError::new(format!("invalid group: {err}")))
+                .on_line(i + 1, first)
+        })
+        .try_collect()?;
+    Ok(rucksacks.into_iter().map(calculate_priority).sum())
 }
 
-fn parse_chunk(chunk: Vec<&String>) -> Rucksack {
-    let common = strings::common_char(&vec![chunk[0], chunk[1], chunk[2]]);
-    Rucksack { common }
+fn parse_chunk(chunk: &[&str; 3]) -> Result<Rucksack, CommonCharError> {
+    let common = strings::common_char(chunk)?;
+    Ok(Rucksack { common })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use itertools::Itertools;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
+    crate::examples::example_tests!(2022, 3);
 
     #[test]
     fn test_part1_inner() {
+        assert_eq!(day3part1_inner("vJrwpWtwJgWrhcsFMMfFFhFp"), Ok(16));
+        assert_eq!(day3part1_inner("jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"), Ok(38));
         assert_eq!(
-            day3part1_inner(to_vec(vec!["vJrwpWtwJgWrhcsFMMfFFhFp"])),
-            16
-        );
-        assert_eq!(
-            day3part1_inner(to_vec(vec!["jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"])),
-            38
-        );
-        assert_eq!(
-            day3part1_inner(to_vec(vec![
-                "vJrwpWtwJgWrhcsFMMfFFhFp",
-                "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"
-            ])),
-            16 + 38
+            day3part1_inner("vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL"),
+            Ok(16 + 38)
         );
     }
 
     #[test]
     fn test_part2_inner() {
         assert_eq!(
-            day3part2_inner(to_vec(vec![
-                "vJrwpWtwJgWrhcsFMMfFFhFp",
-                "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
-                "PmmdzqPrVvPwwTWBwg"
-            ])),
-            18
+            day3part2_inner(
+                "vJrwpWtwJgWrhcsFMMfFFhFp\njqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\nPmmdzqPrVvPwwTWBwg"
+            ),
+            Ok(18)
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = day3part1_inner("vJrwpWtwJgWrhcsFMMfFFhFp\nabcd").unwrap_err();
+        assert_eq!(error.line, Some(2));
+        assert!(error.message.starts_with("invalid rucksack"), "{error}");
+        let error = day3part2_inner("vJrwpWtwJgWrhcsFMMfFFhFp\nabcd").unwrap_err();
+        assert!(
+            error.message.starts_with("Elves must come in groups"),
+            "{error}"
         );
     }
 }
@@ -1,24 +1,19 @@
-use crate::files::lines;
-use crate::geometry::{contains, overlaps};
+use crate::geometry::Interval;
+use crate::parsing::{number, parse_lines, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
 
-pub fn day4part1() -> i32 {
-    let lines_of_file = lines("inputs/2022/day4.txt");
-    part1_inner(lines_of_file)
-}
-
-pub fn day4part2() -> i32 {
-    let lines_of_file = lines("inputs/2022/day4.txt");
-    part2_inner(lines_of_file)
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    Ok(answers(part1_inner(input)?, part2_inner(input)?))
 }
 
 struct Assignments {
-    left: (i32, i32),
-    right: (i32, i32),
+    left: Interval<i32>,
+    right: Interval<i32>,
 }
 
 fn calculate_assignments_containing(assignments: Assignments) -> i32 {
-    if contains(assignments.left, assignments.right)
-        || contains(assignments.right, assignments.left)
+    if assignments.left.contains_interval(&assignments.right)
+        || assignments.right.contains_interval(&assignments.left)
     {
         1
     } else {
@@ -27,82 +22,85 @@ fn calculate_assignments_containing(assignments: Assignments) -> i32 {
 }
 
 fn calculate_assignments_overlapping(assignments: Assignments) -> i32 {
-    if overlaps(assignments.left, assignments.right)
-        || overlaps(assignments.right, assignments.left)
-    {
+    if assignments.left.overlaps(&assignments.right) {
         1
     } else {
         0
     }
 }
 
-fn parse_line(line: &str) -> Result<Assignments, &str> {
-    let error_message = "Can't parse line: {line}";
-    let (left_str, right_str) = line.split_once(",").ok_or(error_message)?;
-    let left_res = parse_assignment(left_str);
-    let right_res = parse_assignment(right_str);
-    match (left_res, right_res) {
-        (Ok(left), Ok(right)) => Ok(Assignments { left, right }),
-        _ => Err(error_message),
-    }
+fn parse_line(line: &str) -> Result<Assignments, ParseError> {
+    let (left_str, right_str) = line
+        .split_once(",")
+        .ok_or_else(|| ParseError::new("expected two assignments separated by a comma"))?;
+    Ok(Assignments {
+        left: parse_assignment(line, left_str)?,
+        right: parse_assignment(line, right_str)?,
+    })
 }
 
-fn parse_assignment(assignment: &str) -> Result<(i32, i32), &str> {
-    let error_message = "Can't parse assignment: {assignment}";
-    let (left_str, right_str) = assignment.split_once("-").ok_or(error_message)?;
-    let left_num_res = left_str.parse::<i32>();
-    let right_num_res = right_str.parse::<i32>();
-    match (left_num_res, right_num_res) {
-        (Ok(left), Ok(right)) => Ok((left, right)),
-        _ => Err(error_message),
-    }
+/// Parses `assignment`, a part of `line`.
+fn parse_assignment(line: &str, assignment: &str) -> Result<Interval<i32>, ParseError> {
+    let (left_str, right_str) = assignment
+        .split_once("-")
+        .ok_or_else(|| ParseError::new(format!("invalid assignment '{assignment}'")))
+        .at(line, assignment)?;
+    Ok(Interval::closed(
+        number(line, left_str)?,
+        number(line, right_str)?,
+    ))
 }
 
-fn part1_inner(lines: Vec<String>) -> i32 {
-    lines
-        .iter()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| parse_line(line))
-        .map(|assignments| calculate_assignments_containing(assignments.unwrap()))
-        .sum()
+fn part1_inner(input: &str) -> Result<i32, ParseError> {
+    let assignments = parse_lines(input, parse_line)?;
+    Ok(assignments
+        .into_iter()
+        .map(calculate_assignments_containing)
+        .sum())
 }
 
-fn part2_inner(lines: Vec<String>) -> i32 {
-    lines
-        .iter()
-        .filter(|line| !line.trim().is_empty())
-        .map(|line| parse_line(line))
-        .map(|assignments| calculate_assignments_overlapping(assignments.unwrap()))
-        .sum()
+fn part2_inner(input: &str) -> Result<i32, ParseError> {
+    let assignments = parse_lines(input, parse_line)?;
+    Ok(assignments
+        .into_iter()
+        .map(calculate_assignments_overlapping)
+        .sum())
 }
 
 #[cfg(test)]
 mod tests {
-    use itertools::Itertools;
-
     use super::*;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
+    crate::examples::example_tests!(2022, 4);
 
     #[test]
     fn test_part1_inner() {
-        assert_eq!(part1_inner(to_vec(vec!["2-4,6-8"])), 0);
-        assert_eq!(part1_inner(to_vec(vec!["2-3,4-5"])), 0);
-        assert_eq!(part1_inner(to_vec(vec!["5-7,7-9"])), 0);
-        assert_eq!(part1_inner(to_vec(vec!["2-8,3-7"])), 1);
-        assert_eq!(part1_inner(to_vec(vec!["6-6,4-6"])), 1);
-        assert_eq!(part1_inner(to_vec(vec!["2-6,4-8"])), 0);
+        assert_eq!(part1_inner("2-4,6-8"), Ok(0));
+        assert_eq!(part1_inner("2-3,4-5"), Ok(0));
+        assert_eq!(part1_inner("5-7,7-9"), Ok(0));
+        assert_eq!(part1_inner("2-8,3-7"), Ok(1));
+        assert_eq!(part1_inner("6-6,4-6"), Ok(1));
+        assert_eq!(part1_inner("2-6,4-8"), Ok(0));
     }
 
     #[test]
     fn test_part2_inner() {
-        assert_eq!(part2_inner(to_vec(vec!["2-4,6-8"])), 0);
-        assert_eq!(part2_inner(to_vec(vec!["2-3,4-5"])), 0);
-        assert_eq!(part2_inner(to_vec(vec!["5-7,7-9"])), 1);
-        assert_eq!(part2_inner(to_vec(vec!["2-8,3-7"])), 1);
-        assert_eq!(part2_inner(to_vec(vec!["6-6,4-6"])), 1);
-        assert_eq!(part2_inner(to_vec(vec!["2-6,4-8"])), 1);
+        assert_eq!(part2_inner("2-4,6-8"), Ok(0));
+        assert_eq!(part2_inner("2-3,4-5"), Ok(0));
+        assert_eq!(part2_inner("5-7,7-9"), Ok(1));
+        assert_eq!(part2_inner("2-8,3-7"), Ok(1));
+        assert_eq!(part2_inner("6-6,4-6"), Ok(1));
+        assert_eq!(part2_inner("2-6,4-8"), Ok(1));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let error = part1_inner("2-4,6-8\n2-3,4_5").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 5: invalid assignment '4_5'\n  |\n2 | 2-3,4_5\n  |     ^"
+        );
+        let error = part1_inner("2-4,6-x").unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(7)));
     }
 }
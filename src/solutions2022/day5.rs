@@ -1,16 +1,20 @@
 use crate::containers::get_mut2;
-use crate::strings::SkipEmptyLines;
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use crate::strings::{integers_array, sections, Section};
+use anyhow::Context;
 use itertools::Itertools;
-use regex::Regex;
+use log::{log_enabled, trace, Level};
 use std::fmt::{Debug, Display, Formatter};
-use std::fs;
+use std::num::NonZeroUsize;
+use std::ops::Range;
 
-pub fn day5() -> (String, String) {
-    let contents = fs::read_to_string("inputs/2022/day5.txt").unwrap();
-    inner(contents).unwrap()
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 struct Movement {
     n: usize,
     source: usize,
@@ -27,157 +31,426 @@ impl Movement {
     }
 }
 
-#[derive(Clone)]
+impl Display for Movement {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "move {} from {} to {}",
+            self.n,
+            self.source + 1,
+            self.target + 1
+        )
+    }
+}
+
+/// Why a movement can't be done. Stacks are numbered from 1, like in the input.
+#[derive(Eq, PartialEq, Debug, Clone)]
+enum MoveError {
+    NoSuchStack {
+        stack: usize,
+        stacks: usize,
+    },
+    NotEnoughCrates {
+        stack: usize,
+        crates: usize,
+        needed: usize,
+    },
+    SameStack(usize),
+}
+
+impl Display for MoveError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MoveError::NoSuchStack { stack, stacks } => {
+                write!(f, "no stack {stack}, there are {stacks} stacks")
+            }
+            MoveError::NotEnoughCrates {
+                stack,
+                crates,
+                needed,
+            } => write!(
+                f,
+                "can't move {needed} crates from stack {stack}, which has {crates}"
+            ),
+            MoveError::SameStack(stack) => {
+                write!(f, "can't move crates from stack {stack} onto itself")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MoveError {}
+
+/// Stacks of crates, from the bottom to the top. Crates are named by what is between their
+/// brackets, which is usually a single letter.
+#[derive(Debug, Clone)]
 struct Cranes {
-    layout: Vec<Vec<char>>,
+    layout: Vec<Vec<String>>,
 }
 
 impl Cranes {
     pub(crate) fn get_top_crates(&self) -> String {
         self.layout
             .iter()
-            .map(|stack| stack.last().unwrap_or(&' '))
+            .map(|stack| stack.last().map_or(" ", String::as_str))
             .join("")
     }
+
+    /// Checks that `movement` can be done, before moving anything.
+    fn check(&self, movement: &Movement) -> Result<(), MoveError> {
+        let stacks = self.layout.len();
+        for stack in [movement.source, movement.target] {
+            if stack >= stacks {
+                let stack = stack + 1;
+                return Err(MoveError::NoSuchStack { stack, stacks });
+            }
+        }
+        if movement.source == movement.target {
+            return Err(MoveError::SameStack(movement.source + 1));
+        }
+        let crates = self.layout[movement.source].len();
+        if crates < movement.n {
+            return Err(MoveError::NotEnoughCrates {
+                stack: movement.source + 1,
+                crates,
+                needed: movement.n,
+            });
+        }
+        Ok(())
+    }
 }
 
+/// Draws the stacks like the input. Columns are wide enough for the longest crate name and the
+/// largest stack number, so that the drawing can be parsed back.
 impl Display for Cranes {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let name_width = self
+            .layout
+            .iter()
+            .flatten()
+            .map(|name| name.chars().count())
+            .max()
+            .unwrap_or(1);
+        let number_width = self.layout.len().to_string().len();
+        let width = (name_width + 2).max(number_width + 1);
         let height = self
             .layout
             .iter()
-            .map(|f| f.len())
+            .map(|stack| stack.len())
             .max()
-            .expect("Invalid layout");
-        let width = self.layout.len();
-        let mut s = String::new();
-
-        for i in (0..height).rev() {
-            for j in 0..width {
-                let stack = &self.layout[j];
-                if i >= stack.len() {
-                    s += "    ";
-                } else {
-                    let string = format!("[{}] ", stack[i]);
-                    s += string.as_str();
-                }
-            }
-            s = format!("{}\n", s.trim_end());
+            .unwrap_or(0);
+
+        let mut lines = Vec::new();
+        for level in (0..height).rev() {
+            let line = self
+                .layout
+                .iter()
+                .map(|stack| match stack.get(level) {
+                    Some(name) => format!("{:<width$}", format!("[{name}]")),
+                    None => " ".repeat(width),
+                })
+                .join(" ");
+            lines.push(line.trim_end().to_string());
         }
-        for i in 0..width {
-            s += format!(" {}  ", i + 1).as_str();
-        }
-        s = format!("{}", s.trim_end());
-        f.write_str(s.as_str())
+        let footer = (1..=self.layout.len())
+            .map(|number| format!(" {number:<0$}", width - 1))
+            .join(" ");
+        lines.push(footer.trim_end().to_string());
+        f.write_str(&lines.join("\n"))
     }
 }
 
 impl Cranes {
-    fn move_one_crate_per_movement(&mut self, movement: &Movement) -> Result<(), String> {
-        for _ in 0..movement.n {
-            let source = &mut self.layout[movement.source];
-            let elem = source
-                .pop()
-                .ok_or("Can't execute movement {movement:?} because the source stack is empty.")?;
-
-            let target = &mut self.layout[movement.target];
-            target.push(elem);
-        }
-        Ok(())
-    }
-    fn move_multiple_crates_per_movement(&mut self, movement: &Movement) -> Result<(), String> {
+    /// Does `movement` with the crane `model`, unless it can't be done.
+    fn apply(&mut self, model: &impl CraneModel, movement: &Movement) -> Result<(), MoveError> {
+        self.check(movement)?;
         let (source, target) =
             get_mut2(self.layout.as_mut_slice(), movement.source, movement.target);
-        if source.len() < movement.n {
-            return Err(format!("Source stack does not have enough crates for this movement. Source stack: {source:?}, movement: {movement:?}"));
+        model.move_crates(source, target, movement.n);
+        Ok(())
+    }
+}
+
+/// How a crane moves crates from one stack to another.
+trait CraneModel: Display {
+    /// Moves `n` crates from the top of `source` to the top of `target`. `source` has at least
+    /// `n` crates.
+    fn move_crates(&self, source: &mut Vec<String>, target: &mut Vec<String>, n: usize);
+}
+
+/// A crane lifting up to `batch` crates at once from the top of a stack, keeping their order.
+#[derive(Debug, Clone, Copy)]
+struct CrateMover {
+    batch: NonZeroUsize,
+}
+
+impl CrateMover {
+    /// Moves one crate at a time, which reverses the order of the crates moved.
+    const MODEL_9000: CrateMover = CrateMover::new(1).unwrap();
+    /// Moves all the crates of a movement at once.
+    const MODEL_9001: CrateMover = CrateMover::new(usize::MAX).unwrap();
+
+    /// A crane lifting up to `batch` crates at once, `None` if `batch` is 0 as it couldn't
+    /// move anything.
+    const fn new(batch: usize) -> Option<CrateMover> {
+        match NonZeroUsize::new(batch) {
+            Some(batch) => Some(CrateMover { batch }),
+            None => None,
         }
-        for elem in source.iter().rev().take(movement.n).rev() {
-            target.push(*elem);
+    }
+}
+
+impl Display for CrateMover {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self.batch.get() {
+            1 => write!(f, "CrateMover 9000"),
+            usize::MAX => write!(f, "CrateMover 9001"),
+            batch => write!(f, "CrateMover moving {batch} crates at once"),
+        }
+    }
+}
+
+impl CraneModel for CrateMover {
+    fn move_crates(&self, source: &mut Vec<String>, target: &mut Vec<String>, n: usize) {
+        let mut left = n;
+        while left > 0 {
+            let lifted = left.min(self.batch.get());
+            target.extend(source.drain(source.len() - lifted..));
+            left -= lifted;
         }
-        source.resize(source.len() - movement.n, ' ');
-        Ok(())
     }
 }
 
-fn inner(input: String) -> Result<(String, String), String> {
-    let input = input.skip_empty_start_lines();
-    let lines = input.lines();
+/// Every state of the stacks while a crane does movements, from the initial one, to go forward
+/// and backward through them. Like undoing, recording from an earlier state drops the states
+/// after it.
+struct Replay {
+    states: Vec<Cranes>,
+    /// The movement from each state to the next one.
+    movements: Vec<Movement>,
+    /// Index of the state shown.
+    position: usize,
+}
 
-    // Now find the division between the crane and the movements
-    let (pos, rest_of_text) = lines
-        .clone()
-        .find_position(|line| line.trim().is_empty())
-        .ok_or("Malformed input")?;
+impl Replay {
+    fn new(cranes: Cranes) -> Replay {
+        Replay {
+            states: vec![cranes],
+            movements: Vec::new(),
+            position: 0,
+        }
+    }
 
-    // TODO: remove these clone() calls
-    let crane_lines = lines.clone().take(pos).collect_vec();
-    let movements_lines = lines.skip(pos + 1).collect_vec();
-    let movements = parse_movements(movements_lines)?;
+    /// Does `movement` with `model` from the current state, recording the new state and showing
+    /// it. Nothing changes when the movement can't be done.
+    fn record(&mut self, model: &impl CraneModel, movement: &Movement) -> Result<(), MoveError> {
+        let mut cranes = self.current().clone();
+        cranes.apply(model, movement)?;
+        self.states.truncate(self.position + 1);
+        self.movements.truncate(self.position);
+        self.states.push(cranes);
+        self.movements.push(movement.clone());
+        self.position += 1;
+        Ok(())
+    }
 
-    let mut cranes_part1 = parse_crane_setup(crane_lines).unwrap();
-    let mut cranes_part2 = cranes_part1.clone();
+    fn current(&self) -> &Cranes {
+        &self.states[self.position]
+    }
 
-    for mov in &movements {
-        cranes_part1.move_one_crate_per_movement(&mov)?;
+    /// The movement leading to the current state, `None` for the initial state.
+    fn movement(&self) -> Option<&Movement> {
+        self.position.checked_sub(1).map(|i| &self.movements[i])
     }
-    let part1 = cranes_part1.get_top_crates();
 
-    for mov in &movements {
-        cranes_part2.move_multiple_crates_per_movement(&mov)?;
+    /// Shows the next state, returns whether there was one.
+    fn forward(&mut self) -> bool {
+        let moved = self.position + 1 < self.states.len();
+        if moved {
+            self.position += 1;
+        }
+        moved
     }
-    let part2 = cranes_part2.get_top_crates();
-    // let part2 = " ".to_string();
 
-    Ok((part1, part2))
+    /// Shows the previous state, returns whether there was one.
+    fn backward(&mut self) -> bool {
+        let moved = self.position > 0;
+        if moved {
+            self.position -= 1;
+        }
+        moved
+    }
 }
 
-fn parse_movements(movements: Vec<&str>) -> Result<Vec<Movement>, String> {
-    let movements = movements.skip_empty_start_lines();
+fn inner(input: &str) -> anyhow::Result<(String, String)> {
+    // Crane and movements are separated by a blank line
+    let (crane_section, movements_section) = sections(input).collect_tuple().ok_or_else(|| {
+        ParseError::new("expected the crane setup and the movements, separated by a blank line")
+    })?;
+    let movements = parse_movements(&movements_section)?;
+    let cranes = parse_crane_setup(&crane_section)?;
+
+    let part1 = rearrange(
+        &cranes,
+        &CrateMover::MODEL_9000,
+        &movements,
+        &movements_section,
+    )?;
+    let part2 = rearrange(
+        &cranes,
+        &CrateMover::MODEL_9001,
+        &movements,
+        &movements_section,
+    )?;
+    Ok((part1.get_top_crates(), part2.get_top_crates()))
+}
 
-    let re = Regex::new(r"^move (?<n>\d+) from (?<source>\d) to (?<target>\d)$").unwrap();
-    movements
+/// Where a movement failed: movements are on consecutive lines, the ones of the parse errors.
+fn movement_context(section: &Section, i: usize, movement: &Movement) -> String {
+    format!("line {}: {movement}", section.start + i + 1)
+}
+
+/// Records every state of `cranes` while `model` does `movements`, showing the last one.
+fn replay(
+    cranes: &Cranes,
+    model: &impl CraneModel,
+    movements: &[Movement],
+    section: &Section,
+) -> anyhow::Result<Replay> {
+    let mut replay = Replay::new(cranes.clone());
+    for (i, mov) in movements.iter().enumerate() {
+        replay
+            .record(model, mov)
+            .with_context(|| movement_context(section, i, mov))?;
+    }
+    Ok(replay)
+}
+
+/// The stacks once `model` did `movements` on `cranes`. With trace logging on, they are replayed
+/// to trace every state, otherwise they are moved in place without keeping any state.
+fn rearrange(
+    cranes: &Cranes,
+    model: &impl CraneModel,
+    movements: &[Movement],
+    section: &Section,
+) -> anyhow::Result<Cranes> {
+    if log_enabled!(Level::Trace) {
+        let mut replay = replay(cranes, model, movements, section)?;
+        let rearranged = replay.current().clone();
+        while replay.backward() {}
+        trace!("{model}:\n{}", replay.current());
+        while replay.forward() {
+            trace!("{}:\n{}", replay.movement().unwrap(), replay.current());
+        }
+        return Ok(rearranged);
+    }
+    let mut cranes = cranes.clone();
+    for (i, mov) in movements.iter().enumerate() {
+        cranes
+            .apply(model, mov)
+            .with_context(|| movement_context(section, i, mov))?;
+    }
+    Ok(cranes)
+}
+
+fn parse_movements(section: &Section) -> Result<Vec<Movement>, ParseError> {
+    section
+        .lines()
         .iter()
-        .map(|line| match re.captures(line) {
-            Some(caps) => {
-                let n = caps["n"].parse::<usize>().unwrap();
-                let source = caps["source"].parse::<usize>().unwrap();
-                let target = caps["target"].parse::<usize>().unwrap();
-                let movement = Movement::new(n, source, target);
-                return Ok(movement);
+        .enumerate()
+        .map(|(i, line)| {
+            let line_number = section.start + i + 1;
+            let [n, source, target] =
+                integers_array::<usize, 3>(line).on_line(line_number, line)?;
+            if source == 0 || target == 0 {
+                let error = ParseError::new("stacks are numbered from 1");
+                return Err(error.on_line(line_number, line));
+            }
+            Ok(Movement::new(n, source, target))
+        })
+        .collect()
+}
+
+/// Whitespace separated tokens of `line`, with the columns, in characters, they span.
+fn tokens(line: &str) -> Vec<(Range<usize>, &str)> {
+    let mut ret = vec![];
+    // Column and byte offset of the token in progress
+    let mut start = None;
+    // A trailing space ends the last token
+    let chars = line.char_indices().chain([(line.len(), ' ')]);
+    for (column, (offset, c)) in chars.enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some((column, offset)),
+            (Some((first_column, first_offset)), true) => {
+                ret.push((first_column..column, &line[first_offset..offset]));
+                start = None;
+            }
+            _ => (),
+        }
+    }
+    ret
+}
+
+/// Columns of each stack, from the numbers of the footer line, which must be 1, 2, 3...
+fn parse_footer(line: &str) -> Result<Vec<Range<usize>>, ParseError> {
+    tokens(line)
+        .into_iter()
+        .enumerate()
+        .map(|(i, (columns, token))| {
+            if number::<usize>(line, token)? != i + 1 {
+                let message = format!("expected stack {}, found '{token}'", i + 1);
+                return Err(ParseError::new(message).at(line, token));
             }
-            None => Err(format!("Invalid input: {line}")),
+            Ok(columns)
         })
         .collect()
 }
 
-fn parse_crane_setup(configuration_lines: Vec<&str>) -> Result<Cranes, &str> {
-    let lines = configuration_lines.skip_empty_start_lines();
-
-    let last_line = lines.last().ok_or("Can't parse ")?;
-    let number_of_stacks = (last_line.len() + 2) / 4;
-
-    let mut layout = Vec::new();
-    for i in 0..number_of_stacks {
-        layout.push(Vec::new());
-    }
-
-    for line in lines.iter().rev().skip(1) {
-        for i in 0..number_of_stacks {
-            let index = i * 4;
-            let crate_name = line.chars().nth(index + 1);
-            match crate_name {
-                Some(c) => {
-                    if c != ' ' {
-                        let stack = &mut layout[i];
-                        stack.push(c);
-                    }
-                }
-                None => continue,
+/// Reads the stacks from the footer line, numbering them, up: every crate, like `[A]`, belongs
+/// to the stack whose number is under it.
+fn parse_crane_setup(section: &Section) -> Result<Cranes, ParseError> {
+    let (footer, crate_lines) = section
+        .lines()
+        .split_last()
+        .ok_or_else(|| ParseError::new("missing crane setup"))?;
+    let footer_number = section.start + crate_lines.len() + 1;
+    let stacks = parse_footer(footer).on_line(footer_number, footer)?;
+
+    let mut layout = vec![Vec::new(); stacks.len()];
+    for (level, (i, line)) in crate_lines.iter().enumerate().rev().enumerate() {
+        let line_number = section.start + i + 1;
+        for (columns, token) in tokens(line) {
+            let error = |message: String| {
+                Err(ParseError::new(message)
+                    .at(line, token)
+                    .on_line(line_number, line))
+            };
+            let name = match token.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+                Some(name) if !name.is_empty() => name,
+                _ => return error(format!("expected a crate like [A], found '{token}'")),
+            }
+            .to_string();
+            let below = stacks
+                .iter()
+                .positions(|stack| stack.start < columns.end && columns.start < stack.end)
+                .collect_vec();
+            let stack = match below[..] {
+                [stack] => stack,
+                [] => return error(format!("crate {token} is not above a stack number")),
+                _ => return error(format!("crate {token} is above several stack numbers")),
+            };
+            let height = layout[stack].len();
+            if height < level {
+                return error(format!("crate {token} floats above stack {}", stack + 1));
             }
+            if height > level {
+                return error(format!("two crates side by side on stack {}", stack + 1));
+            }
+            layout[stack].push(name);
         }
     }
 
-    return Ok(Cranes { layout: layout });
+    Ok(Cranes { layout })
 }
 
 #[cfg(test)]
@@ -190,52 +463,23 @@ mod tests {
     use super::*;
     use crate::strings::SkipEmptyLines;
 
-    #[test]
-    fn test_part1_inner() -> Result<(), String> {
-        assert_eq!(
-            inner(dedent(
-                "
-                [D]
-            [N] [C]
-            [Z] [M] [P]
-             1   2   3
+    crate::examples::example_tests!(2022, 5);
 
-            move 1 from 2 to 1
-            move 3 from 1 to 3
-            move 2 from 2 to 1
-            move 1 from 1 to 2
-            "
-            ))?
-            .0,
-            "CMZ"
-        );
-        Ok(())
+    /// Stacks of single letter crates, from the bottom to the top.
+    fn cranes(stacks: &[&str]) -> Cranes {
+        let layout = stacks
+            .iter()
+            .map(|stack| stack.chars().map(String::from).collect_vec())
+            .collect_vec();
+        Cranes { layout }
     }
 
-    #[test]
-    fn test_part2_inner() -> Result<(), String> {
-        assert_eq!(
-            inner(dedent(
-                "
-                [D]
-            [N] [C]
-            [Z] [M] [P]
-             1   2   3
-
-            move 1 from 2 to 1
-            move 3 from 1 to 3
-            move 2 from 2 to 1
-            move 1 from 1 to 2
-            "
-            ))?
-            .1,
-            "MCD"
-        );
-        Ok(())
+    fn parse_setup(setup: &str) -> Result<Cranes, ParseError> {
+        parse_crane_setup(&sections(setup).next().unwrap())
     }
 
     #[test]
-    fn test_parse_crane_setup() -> Result<(), String> {
+    fn test_parse_crane_setup() -> Result<(), ParseError> {
         let crane_layout = dedent(
             "
                 [D]
@@ -244,7 +488,7 @@ mod tests {
              1   2   3",
         )
         .skip_empty_start_lines();
-        let cranes = parse_crane_setup(crane_layout.lines().collect_vec())?;
+        let cranes = parse_setup(&crane_layout)?;
         assert_eq!(cranes.layout.len(), 3);
         assert_eq!(
             cranes.layout.iter().map(|v| v.len()).collect_vec(),
@@ -256,7 +500,7 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_crane_setup_from_day5() -> Result<(), String> {
+    fn test_parse_crane_setup_from_day5() -> Result<(), ParseError> {
         let crane_layout = dedent(
             "
             [F]         [L]     [M]
@@ -269,18 +513,153 @@ mod tests {
             [J] [V] [G] [B] [F] [G] [D] [H] [G]
              1   2   3   4   5   6   7   8   9",
         );
-        let actual = parse_crane_setup(crane_layout.lines().collect_vec())?;
+        let actual = parse_setup(&crane_layout)?;
         assert_eq!(actual.layout.len(), 9);
         assert_eq!(actual.to_string(), crane_layout.skip_empty_start_lines());
         Ok(())
     }
 
     #[test]
-    fn test_move_one_crate_per_movement() {
-        let mut cranes = Cranes {
-            layout: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
+    fn test_parse_crane_setup_with_many_stacks() -> Result<(), ParseError> {
+        let crane_layout = dedent(
+            "
+                                                [K]
+            [A] [B] [C] [D] [E] [F] [G] [H] [I] [J]
+             1   2   3   4   5   6   7   8   9   10  11",
+        );
+        let cranes = parse_setup(&crane_layout)?;
+        assert_eq!(cranes.layout.len(), 11);
+        assert_eq!(cranes.layout[9], ["J", "K"]);
+        assert!(cranes.layout[10].is_empty());
+        assert_eq!(cranes.get_top_crates(), "ABCDEFGHIK ");
+        assert_eq!(cranes.to_string(), crane_layout.skip_empty_start_lines());
+        Ok(())
+    }
+
+    #[test]
+    fn test_tokens() {
+        assert_eq!(
+            tokens(" [é]\u{3000}[Ab]  1"),
+            vec![(1..4, "[é]"), (5..9, "[Ab]"), (11..12, "1")]
+        );
+        assert_eq!(tokens("   "), vec![]);
+    }
+
+    #[test]
+    fn test_parse_crane_setup_with_long_names() -> Result<(), ParseError> {
+        let cranes = parse_setup("[Ab] [é]\n[C]  [De]\n 1    2")?;
+        assert_eq!(cranes.layout, [vec!["C", "Ab"], vec!["De", "é"]]);
+        expect![["
+            [Ab] [é]
+            [C]  [De]
+             1    2"]]
+        .assert_eq(&cranes.to_string());
+        assert_eq!(parse_setup(&cranes.to_string())?.layout, cranes.layout);
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_crane_setup_errors() {
+        let error = |setup: &str| {
+            let error = parse_setup(setup).unwrap_err();
+            (error.message, error.line, error.column)
         };
-        cranes.move_one_crate_per_movement(&Movement::new(1, 2, 1));
+        assert_eq!(
+            error("[A] [B]\n 1   3"),
+            ("expected stack 2, found '3'".to_string(), Some(2), Some(6))
+        );
+        assert_eq!(
+            error("[A]\n[B]"),
+            (
+                "invalid number '[B]': invalid digit found in string".to_string(),
+                Some(2),
+                Some(1)
+            )
+        );
+        assert_eq!(
+            error("    [A]\n[B]\n 1   2"),
+            (
+                "crate [A] floats above stack 2".to_string(),
+                Some(1),
+                Some(5)
+            )
+        );
+        assert_eq!(
+            error("[A]     [B]\n 1   2"),
+            (
+                "crate [B] is not above a stack number".to_string(),
+                Some(1),
+                Some(9)
+            )
+        );
+        assert_eq!(
+            error("[A] B\n 1   2"),
+            (
+                "expected a crate like [A], found 'B'".to_string(),
+                Some(1),
+                Some(5)
+            )
+        );
+    }
+
+    #[test]
+    fn test_check_movements() {
+        let cranes = cranes(&["ZN", "MCD", "P"]);
+        assert_eq!(cranes.check(&Movement::new(3, 2, 1)), Ok(()));
+        assert_eq!(
+            cranes.check(&Movement::new(1, 4, 1)),
+            Err(MoveError::NoSuchStack {
+                stack: 4,
+                stacks: 3
+            })
+        );
+        assert_eq!(
+            cranes.check(&Movement::new(2, 3, 1)),
+            Err(MoveError::NotEnoughCrates {
+                stack: 3,
+                crates: 1,
+                needed: 2
+            })
+        );
+        assert_eq!(
+            cranes.check(&Movement::new(1, 1, 1)),
+            Err(MoveError::SameStack(1))
+        );
+
+        // Failed movements leave the stacks as they were
+        let mut moved = cranes.clone();
+        assert!(moved
+            .apply(&CrateMover::MODEL_9000, &Movement::new(3, 1, 2))
+            .is_err());
+        assert_eq!(moved.to_string(), cranes.to_string());
+
+        let input = "[A]\n[B] [C]\n 1   2\n\nmove 2 from 1 to 2\nmove 4 from 2 to 1";
+        let error = inner(input).unwrap_err();
+        assert_eq!(
+            format!("{error:#}"),
+            "line 6: move 4 from 2 to 1: can't move 4 crates from stack 2, which has 3"
+        );
+    }
+
+    #[test]
+    fn test_parse_movements_errors() {
+        let input = "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 1 from 0 to 1";
+        let error = inner(input).unwrap_err().downcast::<ParseError>().unwrap();
+        assert_eq!(error.message, "stacks are numbered from 1");
+        assert_eq!(error.line, Some(6));
+
+        let input = "    [D]\n[N] [C]\n 1   2\n\nmove 1 from 2 to 1\nmove 1 from 2";
+        let error = inner(input).unwrap_err().downcast::<ParseError>().unwrap();
+        assert_eq!(error.message, "expected 3 integers, found 2");
+        assert_eq!(error.line, Some(6));
+    }
+
+    #[test]
+    fn test_move_one_crate_per_movement() {
+        let mut cranes = cranes(&["ZN", "MCD", "P"]);
+        cranes
+            .apply(&CrateMover::MODEL_9000, &Movement::new(1, 2, 1))
+            .unwrap();
         expect![["
             [D]
             [N] [C]
@@ -291,9 +670,7 @@ mod tests {
 
     #[test]
     fn test_move_multiple_crates_per_movement() {
-        let mut cranes = Cranes {
-            layout: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
-        };
+        let mut cranes = cranes(&["ZN", "MCD", "P"]);
         expect![["
                 [D]
             [N] [C]
@@ -301,7 +678,9 @@ mod tests {
              1   2   3"]]
         .assert_eq(&cranes.to_string());
 
-        cranes.move_multiple_crates_per_movement(&Movement::new(1, 2, 1));
+        cranes
+            .apply(&CrateMover::MODEL_9001, &Movement::new(1, 2, 1))
+            .unwrap();
         expect![["
             [D]
             [N] [C]
@@ -309,7 +688,9 @@ mod tests {
              1   2   3"]]
         .assert_eq(&cranes.to_string());
 
-        cranes.move_multiple_crates_per_movement(&Movement::new(2, 1, 3));
+        cranes
+            .apply(&CrateMover::MODEL_9001, &Movement::new(2, 1, 3))
+            .unwrap();
         expect![["
                     [D]
                 [C] [N]
@@ -320,9 +701,7 @@ mod tests {
 
     #[test]
     fn test_display_crane() {
-        let cranes = Cranes {
-            layout: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
-        };
+        let cranes = cranes(&["ZN", "MCD", "P"]);
         expect![["
                 [D]
             [N] [C]
@@ -333,9 +712,7 @@ mod tests {
 
     #[test]
     fn test_cranes_clone() {
-        let cranes1 = Cranes {
-            layout: vec![vec!['Z', 'N'], vec!['M', 'C', 'D'], vec!['P']],
-        };
+        let cranes1 = cranes(&["ZN", "MCD", "P"]);
         let mut cranes2 = cranes1.clone();
         let cranes1_string = cranes1.to_string();
         assert_eq!(cranes1_string, cranes2.to_string());
@@ -343,4 +720,99 @@ mod tests {
         cranes2.layout[0].pop();
         assert_ne!(cranes1_string, cranes2.to_string());
     }
+
+    /// Lifts crates from the bottom of the source stack, keeping their order.
+    struct BottomLifter;
+
+    impl Display for BottomLifter {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "Bottom lifter")
+        }
+    }
+
+    impl CraneModel for BottomLifter {
+        fn move_crates(&self, source: &mut Vec<String>, target: &mut Vec<String>, n: usize) {
+            target.extend(source.drain(..n));
+        }
+    }
+
+    #[test]
+    fn test_crane_models() {
+        let movement = Movement::new(4, 1, 2);
+        let moved = |model: &dyn Fn(&mut Cranes) -> Result<(), MoveError>| {
+            let mut cranes = cranes(&["ABCDE", ""]);
+            model(&mut cranes).unwrap();
+            cranes.layout[1].join("")
+        };
+        assert_eq!(
+            moved(&|cranes| cranes.apply(&CrateMover::MODEL_9000, &movement)),
+            "EDCB"
+        );
+        assert_eq!(
+            moved(&|cranes| cranes.apply(&CrateMover::MODEL_9001, &movement)),
+            "BCDE"
+        );
+        assert_eq!(
+            moved(&|cranes| cranes.apply(&CrateMover::new(3).unwrap(), &movement)),
+            "CDEB"
+        );
+        assert_eq!(
+            moved(&|cranes| cranes.apply(&BottomLifter, &movement)),
+            "ABCD"
+        );
+
+        assert_eq!(CrateMover::MODEL_9001.to_string(), "CrateMover 9001");
+        assert_eq!(
+            CrateMover::new(3).unwrap().to_string(),
+            "CrateMover moving 3 crates at once"
+        );
+        assert!(CrateMover::new(0).is_none());
+    }
+
+    #[test]
+    fn test_replay() {
+        let movements = [Movement::new(1, 2, 1), Movement::new(3, 1, 3)];
+        let mut replay = Replay::new(cranes(&["ZN", "MCD", "P"]));
+        for movement in &movements {
+            replay.record(&CrateMover::MODEL_9000, movement).unwrap();
+        }
+        let error = replay.record(&CrateMover::MODEL_9000, &Movement::new(1, 1, 2));
+        assert_eq!(
+            error,
+            Err(MoveError::NotEnoughCrates {
+                stack: 1,
+                crates: 0,
+                needed: 1
+            })
+        );
+        assert_eq!(replay.current().get_top_crates(), " CZ");
+        // Every line is indented, which expect! would strip
+        assert_eq!(
+            replay.current().to_string(),
+            "        [Z]\n        [N]\n    [C] [D]\n    [M] [P]\n 1   2   3"
+        );
+        assert!(!replay.forward());
+
+        assert!(replay.backward());
+        assert!(replay.backward());
+        assert!(replay.movement().is_none());
+        assert!(!replay.backward());
+        assert!(replay.forward());
+        assert_eq!(replay.movement().unwrap().to_string(), "move 1 from 2 to 1");
+        expect![["
+            [D]
+            [N] [C]
+            [Z] [M] [P]
+             1   2   3"]]
+        .assert_eq(&replay.current().to_string());
+
+        // Recording from there drops the last state
+        replay
+            .record(&CrateMover::MODEL_9001, &Movement::new(2, 1, 3))
+            .unwrap();
+        assert_eq!(replay.current().get_top_crates(), "ZCD");
+        assert!(!replay.forward());
+        assert!(replay.backward());
+        assert_eq!(replay.movement().unwrap().to_string(), "move 1 from 2 to 1");
+    }
 }
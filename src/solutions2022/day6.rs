@@ -1,14 +1,14 @@
+use crate::registry::{answers, Answers};
 use crate::strings::SkipEmptyLines;
 use itertools::Itertools;
 use std::collections::HashSet;
 use std::fmt::{Debug, Display};
-use std::fs;
 
 type PuzzleResult = usize;
 
-pub fn day6() -> (PuzzleResult, PuzzleResult) {
-    let contents = fs::read_to_string("inputs/2022/day6.txt").unwrap();
-    inner(contents).unwrap()
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let (part1, part2) = inner(input.to_string()).map_err(anyhow::Error::msg)?;
+    Ok(answers(part1, part2))
 }
 
 fn inner(input: String) -> Result<(PuzzleResult, PuzzleResult), String> {
@@ -37,6 +37,8 @@ mod tests {
     use pretty_assertions::assert_eq;
     use textwrap::dedent;
 
+    crate::examples::example_tests!(2022, 6);
+
     #[test]
     fn test_part1_inner() -> Result<(), String> {
         assert_eq!(inner("abcad".to_string())?.0, 5);
@@ -1,4 +1,6 @@
-use crate::files::lines;
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use crate::strings::to_lines;
 use indextree::{Arena, NodeId};
 use itertools::Itertools;
 use regex::Regex;
@@ -10,9 +12,9 @@ use std::rc::Rc;
 
 type PuzzleResult = usize;
 
-pub fn day7() -> (PuzzleResult, PuzzleResult) {
-    let lines_of_file = lines("inputs/2022/day7.txt");
-    inner(lines_of_file)
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let (part1, part2) = inner(to_lines(input))?;
+    Ok(answers(part1, part2))
 }
 
 #[derive(Debug, PartialEq)]
@@ -33,63 +35,68 @@ enum IntermediateResult {
     OutputLine(String),
 }
 
-fn parse_intermediate_results(lines: Vec<String>) -> Result<Vec<IntermediateResult>, String> {
+/// Commands and output lines, with their line numbers.
+fn parse_intermediate_results(
+    lines: &[String],
+) -> Result<Vec<(usize, IntermediateResult)>, ParseError> {
     let re = Regex::new(r"^\$ (?<command>\S+)\s?(?<args>.*)$").unwrap();
 
     let commands_and_output = lines
         .iter()
-        .map(|line| match re.captures(line) {
+        .enumerate()
+        .map(|(i, line)| match re.captures(line) {
             Some(caps) => {
                 let command_string = caps["command"].to_string();
                 let args = caps.name("args").map(|m| m.as_str().to_string());
-                IntermediateResult::Command(command_string, args)
+                (i + 1, IntermediateResult::Command(command_string, args))
             }
-            None => IntermediateResult::OutputLine(line.to_string()),
+            None => (i + 1, IntermediateResult::OutputLine(line.to_string())),
         })
         .collect_vec();
-    let first_command = commands_and_output.first().unwrap();
-    if let IntermediateResult::OutputLine(_) = first_command {
-        return Err("Wrong input. The first line is not a command".to_string());
+    if let Some((line_number, IntermediateResult::OutputLine(line))) = commands_and_output.first() {
+        let error = ParseError::new("the first line is not a command");
+        return Err(error.on_line(*line_number, line));
     }
     return Ok(commands_and_output);
 }
 
-fn parse_commands(lines: Vec<String>) -> Result<Vec<Commands>, String> {
+fn parse_commands(lines: Vec<String>) -> Result<Vec<Commands>, ParseError> {
     if lines.is_empty() {
         return Ok(Vec::new());
     }
 
-    let commands_and_output = parse_intermediate_results(lines)?;
+    let commands_and_output = parse_intermediate_results(&lines)?;
 
     let mut outputs = Vec::new();
     let mut ret = Vec::new();
-    for res in commands_and_output.iter().rev() {
+    for (line_number, res) in commands_and_output.iter().rev() {
+        let line = &lines[line_number - 1];
         match res {
             IntermediateResult::Command(name, args) => {
                 let command = match name.as_str() {
                     "ls" => {
                         outputs.reverse();
-                        let ls_outputs = parse_ls_outputs(&outputs);
+                        let ls_outputs = parse_ls_outputs(&outputs)?;
                         outputs.truncate(0);
                         Commands::Ls(ls_outputs)
                     }
                     "cd" => {
-                        let option = args.as_ref();
-                        Commands::Cd(
-                            option
-                                .expect("The cd command must always have arguments")
-                                .clone(),
-                        )
+                        let directory = args
+                            .as_ref()
+                            .filter(|args| !args.is_empty())
+                            .ok_or_else(|| ParseError::new("the cd command must have an argument"))
+                            .on_line(*line_number, line)?;
+                        Commands::Cd(directory.clone())
                     }
                     _ => {
-                        let error_message = format!("Unknown command: {name}");
-                        return Err(error_message);
+                        let error = ParseError::new(format!("unknown command '{name}'"));
+                        return Err(error.at(line, name).on_line(*line_number, line));
                     }
                 };
                 ret.push(command);
             }
             IntermediateResult::OutputLine(line) => {
-                outputs.push(line.clone());
+                outputs.push((*line_number, line.as_str()));
             }
         }
     }
@@ -97,21 +104,23 @@ fn parse_commands(lines: Vec<String>) -> Result<Vec<Commands>, String> {
     Ok(ret)
 }
 
-fn parse_ls_outputs(lines: &Vec<String>) -> Vec<LsOutput> {
+/// Parses the lines, with their numbers, output by an `ls` command.
+fn parse_ls_outputs(lines: &[(usize, &str)]) -> Result<Vec<LsOutput>, ParseError> {
     lines
         .iter()
-        .map(|line| {
-            let error_message = format!("Invalid output line for 'ls' command: {line}");
-            let (a, b) = line.split_once(" ").expect(error_message.as_str());
-            return if a == "dir" {
+        .map(|&(line_number, line)| {
+            let (a, b) = line
+                .split_once(" ")
+                .ok_or_else(|| ParseError::new("invalid output line for the 'ls' command"))
+                .on_line(line_number, line)?;
+            Ok(if a == "dir" {
                 LsOutput::Directory(b.to_string())
             } else {
-                let error_message = format!("Invalid file size for file {b}: {a}");
-                let file_size = a.parse::<usize>().expect(error_message.as_str());
+                let file_size = number::<usize>(line, a).on_line(line_number, line)?;
                 LsOutput::File(b.to_string(), file_size)
-            };
+            })
         })
-        .collect_vec()
+        .collect()
 }
 
 #[derive(Debug, PartialEq)]
@@ -309,8 +318,8 @@ fn calculate_recursive_size(filesystem: &Filesystem) -> usize {
         .sum()
 }
 
-fn inner(lines: Vec<String>) -> (PuzzleResult, PuzzleResult) {
-    let commands = parse_commands(lines).unwrap();
+fn inner(lines: Vec<String>) -> Result<(PuzzleResult, PuzzleResult), ParseError> {
+    let commands = parse_commands(lines)?;
     let root = build_filesystem_from_commands(commands);
     let part1 = root
         .walk()
@@ -331,12 +340,11 @@ fn inner(lines: Vec<String>) -> (PuzzleResult, PuzzleResult) {
         .min_by_key(|(name, size)| *size)
         .unwrap();
     let part2 = part2_dir.1;
-    (part1, part2)
+    Ok((part1, part2))
 }
 
 #[cfg(test)]
 mod tests {
-    use itertools::Itertools;
     use map_macro::hash_map;
     use textwrap::dedent;
 
@@ -344,80 +352,7 @@ mod tests {
     use crate::strings::SkipEmptyLines;
     use pretty_assertions::assert_eq;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    #[test]
-    fn test_part1_inner() -> Result<(), String> {
-        let commands_string = dedent(
-            "
-            $ cd /
-            $ ls
-            dir a
-            14848514 b.txt
-            8504156 c.dat
-            dir d
-            $ cd a
-            $ ls
-            dir e
-            29116 f
-            2557 g
-            62596 h.lst
-            $ cd e
-            $ ls
-            584 i
-            $ cd ..
-            $ cd ..
-            $ cd d
-            $ ls
-            4060174 j
-            8033020 d.log
-            5626152 d.ext
-            7214296 k",
-        )
-        .skip_empty_start_lines();
-        let result = inner(to_vec(commands_string.lines().collect_vec())).0;
-
-        assert_eq!(result, 95437);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_inner() -> Result<(), String> {
-        let commands_string = dedent(
-            "
-            $ cd /
-            $ ls
-            dir a
-            14848514 b.txt
-            8504156 c.dat
-            dir d
-            $ cd a
-            $ ls
-            dir e
-            29116 f
-            2557 g
-            62596 h.lst
-            $ cd e
-            $ ls
-            584 i
-            $ cd ..
-            $ cd ..
-            $ cd d
-            $ ls
-            4060174 j
-            8033020 d.log
-            5626152 d.ext
-            7214296 k",
-        )
-        .skip_empty_start_lines();
-        let result = inner(to_vec(commands_string.lines().collect_vec())).1;
-        assert_eq!(result, 24933642);
-
-        Ok(())
-    }
+    crate::examples::example_tests!(2022, 7);
 
     #[test]
     fn test_walk() -> Result<(), String> {
@@ -428,7 +363,7 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_commands() -> Result<(), String> {
+    fn test_parse_commands() -> Result<(), ParseError> {
         let commands_string = dedent(
             "
             $ cd /
@@ -440,7 +375,7 @@ mod tests {
         ",
         )
         .skip_empty_start_lines();
-        let commands = parse_commands(to_vec(commands_string.lines().collect_vec()))?;
+        let commands = parse_commands(to_lines(&commands_string))?;
         assert_eq!(commands.len(), 2);
 
         assert_eq!(commands[0], Commands::Cd("/".to_string()));
@@ -455,7 +390,20 @@ mod tests {
     }
 
     #[test]
-    fn test_build_filesystem_representation() -> Result<(), String> {
+    fn test_parse_commands_errors() {
+        let error = parse_commands(to_lines("$ cd /\n$ ls\ndir a\n12x b.txt")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 4, column 1: invalid number '12x': invalid digit found in string\n  |\n4 | 12x b.txt\n  | ^"
+        );
+        let error = parse_commands(to_lines("$ cd /\n$ rm -rf a")).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        let error = parse_commands(to_lines("dir a")).unwrap_err();
+        assert_eq!(error.message, "the first line is not a command");
+    }
+
+    #[test]
+    fn test_build_filesystem_representation() -> Result<(), ParseError> {
         let filesystem = filesystem();
         let commands_string = dedent(
             "
@@ -472,7 +420,7 @@ mod tests {
         ",
         )
         .skip_empty_start_lines();
-        let commands = parse_commands(to_vec(commands_string.lines().collect_vec()))?;
+        let commands = parse_commands(to_lines(&commands_string))?;
         let root = build_filesystem_from_commands(commands);
         assert_eq!(root, filesystem);
         Ok(())
@@ -1,16 +1,18 @@
-use crate::files::lines;
 use itertools::Itertools;
 use ndarray::prelude::*;
 use std::cmp::max;
 use std::fmt::{Debug, Formatter};
 use std::iter::zip;
 use crate::array;
+use crate::parsing::ParseError;
+use crate::registry::{answers, Answers};
+use crate::strings::to_lines;
 
 type PuzzleResult = usize;
 
-pub fn day8() -> (PuzzleResult, PuzzleResult) {
-    let lines_of_file = lines("inputs/2022/day8.txt");
-    inner(lines_of_file)
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let (part1, part2) = inner(to_lines(input))?;
+    Ok(answers(part1, part2))
 }
 
 struct Grid {
@@ -121,15 +123,23 @@ impl Grid {
         }
     }
 
-    fn parse_lines(lines: Vec<String>) -> Result<Grid, String> {
+    fn parse_lines(lines: Vec<String>) -> Result<Grid, ParseError> {
         if lines.is_empty() || lines[0].is_empty() {
-            return Err("Invalid lines!".to_string());
+            return Err(ParseError::new("empty grid"));
         }
-        let mut arr = Array2::zeros((lines.len(), lines[0].len()));
+        let width = lines[0].chars().count();
+        let mut arr = Array2::zeros((lines.len(), width));
         for (i, line) in lines.iter().enumerate() {
-            for (j, c) in line.chars().enumerate() {
-                let val = c.to_string().parse::<u8>().map_err(|err| err.to_string())?;
-                arr[[i, j]] = val;
+            let error = |message: String| ParseError::new(message).on_line(i + 1, line);
+            if line.chars().count() != width {
+                return Err(error(format!("expected {width} trees like the first line")));
+            }
+            for (j, (offset, c)) in line.char_indices().enumerate() {
+                let val = c.to_digit(10).ok_or_else(|| {
+                    let tree = &line[offset..offset + c.len_utf8()];
+                    error(format!("invalid tree height '{c}'")).at(line, tree)
+                })?;
+                arr[[i, j]] = val as u8;
             }
         }
         let grid = Grid { elems: arr };
@@ -151,59 +161,22 @@ impl Debug for Grid {
     }
 }
 
-fn inner(lines: Vec<String>) -> (PuzzleResult, PuzzleResult) {
-    let grid = Grid::parse_lines(lines).unwrap();
+fn inner(lines: Vec<String>) -> Result<(PuzzleResult, PuzzleResult), ParseError> {
+    let grid = Grid::parse_lines(lines)?;
     let part1 = grid.number_of_edge_trees() + grid.count_inner_visible_trees();
     let part2 = grid.find_max_scenic_score();
-    (part1, part2)
+    Ok((part1, part2))
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::strings::SkipEmptyLines;
-    use itertools::Itertools;
     use ndarray::array;
     use pretty_assertions::assert_eq;
     use textwrap::dedent;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    #[test]
-    fn test_part1_inner() -> Result<(), String> {
-        let grid_string = dedent(
-            "
-            30373
-            25512
-            65332
-            33549
-            35390",
-        )
-        .skip_empty_start_lines();
-        let actual = inner(to_vec(grid_string.lines().collect_vec())).0;
-        assert_eq!(actual, 21);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_inner() -> Result<(), String> {
-        let grid_string = dedent(
-            "
-            30373
-            25512
-            65332
-            33549
-            35390",
-        )
-        .skip_empty_start_lines();
-        let actual = inner(to_vec(grid_string.lines().collect_vec())).1;
-        assert_eq!(actual, 8);
-
-        Ok(())
-    }
+    crate::examples::example_tests!(2022, 8);
 
     fn grid() -> Grid {
         Grid {
@@ -218,7 +191,7 @@ mod tests {
     }
 
     #[test]
-    fn test_parse_grid() -> Result<(), String> {
+    fn test_parse_grid() -> Result<(), ParseError> {
         let grid_string = dedent(
             "
             30373
@@ -228,7 +201,7 @@ mod tests {
             35390",
         )
         .skip_empty_start_lines();
-        let lines = to_vec(grid_string.lines().collect_vec());
+        let lines = to_lines(&grid_string);
         let grid = Grid::parse_lines(lines)?;
         assert_eq!(
             grid.elems,
@@ -245,6 +218,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_grid_errors() {
+        let error = Grid::parse_lines(to_lines("303\n2x5")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 2: invalid tree height 'x'\n  |\n2 | 2x5\n  |  ^"
+        );
+        let error = Grid::parse_lines(to_lines("303\n25")).unwrap_err();
+        assert_eq!(error.message, "expected 3 trees like the first line");
+    }
+
     #[test]
     fn test_find_inner_visible_trees() -> Result<(), String> {
         let grid = grid();
@@ -265,7 +249,7 @@ mod tests {
     //         241244443464555",
     //     )
     //     .skip_empty_start_lines();
-    //     let lines = to_vec(grid_string.lines().collect_vec());
+    //     let lines = to_lines(&grid_string);
     //     let grid = Grid::parse_lines(lines)?;
     //
     //     let mask_bool = grid.compute_visible_trees_mask();
@@ -1,102 +1,222 @@
-use crate::containers::get_mut2;
-use crate::files::lines;
+use crate::containers::pairs_mut;
+use crate::dumps;
+use crate::geometry::{Direction, Position};
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use crate::strings::to_lines;
+use anyhow::Context;
 use itertools::Itertools;
+use log::trace;
 // use ndarray::prelude::*;
 use std::collections::HashSet;
 use std::fmt::{Debug, Display, Formatter, Write};
-use std::ops;
+use std::fs::File;
+use std::io::{self, BufWriter};
+use std::path::Path;
 
 type PuzzleResult = usize;
 
-pub fn day9() -> (PuzzleResult, PuzzleResult) {
-    let lines_of_file = lines("inputs/2022/day9.txt");
-    inner(lines_of_file)
+/// Name of the dump of the frames of the 10-knot rope, written when dumps are asked for.
+const FRAMES_DUMP: &str = "2022-day9-frames.txt";
+
+pub fn solve(input: &str) -> anyhow::Result<Answers> {
+    let movements = parse(to_lines(input))?;
+    let (part1, part2) = inner(&movements);
+    if let Some(path) = dumps::path(FRAMES_DUMP) {
+        dump_frames(&path, &movements)
+            .with_context(|| format!("Can't write {}", path.display()))?;
+        trace!("Frames of the rope written to {}", path.display());
+    }
+    Ok(answers(part1, part2))
 }
 
-#[derive(Eq, Hash, Ord, PartialOrd, PartialEq, Debug, Copy, Clone)]
-pub struct Position(pub i32, pub i32);
+/// When a knot is too far from the knot before it, and how it catches up.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+enum FollowRule {
+    /// Knots touch when they are adjacent, diagonally included, and catch up diagonally: the
+    /// rule of the puzzle.
+    Chebyshev,
+    /// Knots touch only when they are adjacent horizontally or vertically, and catch up one
+    /// horizontal or vertical step at a time, along the axis they are the furthest on. Not
+    /// needed by the puzzle.
+    #[allow(dead_code)]
+    Manhattan,
+}
 
-impl Position {
-    fn manhattan_distance(&self, other: &Position) -> u32 {
-        self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
-    }
-    fn euclidean_distance(&self, other: &Position) -> u32 {
-        let inner = (self.0 - other.0).pow(2) + (self.1 - other.1).pow(2);
-        let sqrt = (inner as f64).sqrt();
-        let rounded_up = sqrt.floor();
-        rounded_up as u32
+impl FollowRule {
+    fn touching(&self, knot: &Position, leader: &Position) -> bool {
+        match self {
+            FollowRule::Chebyshev => knot.touching(leader),
+            FollowRule::Manhattan => knot.manhattan_distance(leader) <= 1,
+        }
     }
-    fn touching(&self, other: &Position) -> bool {
-        self.0.abs_diff(other.0) <= 1 && self.1.abs_diff(other.1) <= 1
+
+    fn step_towards(&self, knot: &Position, leader: &Position) -> Position {
+        let offset = *leader - *knot;
+        let Position(dx, dy) = offset;
+        match self {
+            FollowRule::Chebyshev => offset.signum(),
+            FollowRule::Manhattan if dx.abs() >= dy.abs() => Position(dx.signum(), 0),
+            FollowRule::Manhattan => Position(0, dy.signum()),
+        }
     }
 }
 
-impl Position {}
+/// A rope of knots starting at the origin, each following the one before it. The head is the
+/// first knot and the tail the last one. Only the current positions are kept, unless histories
+/// are recorded: a [Simulation] tells where the tail has been in constant memory.
+#[derive(Clone)]
+struct Rope {
+    knots: Vec<Position>,
+    rule: FollowRule,
+    /// Position of each knot after each step, starting with the initial one, when recorded.
+    histories: Option<Vec<Vec<Position>>>,
+}
 
-impl ops::Sub<Position> for Position {
-    type Output = Position;
+impl Rope {
+    fn new(rope_size: usize) -> Rope {
+        Rope::with_rule(rope_size, FollowRule::Chebyshev)
+    }
 
-    fn sub(self, rhs: Position) -> Self::Output {
-        Position(self.0 - rhs.0, self.1 - rhs.1)
+    fn with_rule(rope_size: usize, rule: FollowRule) -> Rope {
+        assert!(rope_size > 0, "A rope needs at least one knot");
+        Rope {
+            knots: vec![Position(0, 0); rope_size],
+            rule,
+            histories: None,
+        }
     }
-}
 
-impl ops::Add<(i32, i32)> for Position {
-    type Output = Position;
+    /// Records every position of every knot from now on, for [Rope::history] and
+    /// [Rope::visited]. Memory grows with the number of steps.
+    fn with_histories(mut self) -> Rope {
+        self.histories = Some(self.knots.iter().map(|knot| vec![*knot]).collect_vec());
+        self
+    }
 
-    fn add(self, rhs: (i32, i32)) -> Position {
-        let x = self.0 + rhs.0;
-        let y = self.1 + rhs.1;
-        Position(x, y)
+    fn knots(&self) -> &[Position] {
+        &self.knots
     }
-}
 
-impl ops::AddAssign<(i32, i32)> for Position {
-    fn add_assign(&mut self, rhs: (i32, i32)) {
-        self.0 += rhs.0;
-        self.1 += rhs.1;
+    fn tail(&self) -> Position {
+        *self.knots.last().unwrap()
     }
-}
 
-#[derive(Clone)]
-struct Rope(Vec<Position>);
+    /// Moves the head by `offset`, one step in any of the 8 directions, and then every knot
+    /// until it touches the one before it.
+    fn step(&mut self, offset: Position) {
+        self.knots[0] += offset;
+        let rule = self.rule;
+        let mut knots = pairs_mut(&mut self.knots);
+        while let Some((leader, knot)) = knots.next_pair() {
+            while !rule.touching(knot, leader) {
+                *knot += rule.step_towards(knot, leader);
+            }
+        }
+        if let Some(histories) = &mut self.histories {
+            for (history, knot) in histories.iter_mut().zip(&self.knots) {
+                history.push(*knot);
+            }
+        }
+    }
 
-impl Rope {
-    fn new(rope_size: usize) -> Rope {
-        Rope(vec![Position(0, 0); rope_size])
+    /// Positions of the `knot`th knot, from the head at 0, after each step. Only for ropes made
+    /// [Rope::with_histories].
+    fn history(&self, knot: usize) -> &[Position] {
+        let histories = self.histories.as_ref();
+        &histories.expect("Histories of the rope are not recorded")[knot]
     }
 
-    fn from_vec(knots: Vec<(i32, i32)>) -> Rope {
-        let positions = knots.iter().map(|(x, y)| Position(*x, *y)).collect_vec();
-        Rope(positions)
+    /// Positions the `knot`th knot, from the head at 0, has been at. Only for ropes made
+    /// [Rope::with_histories].
+    fn visited(&self, knot: usize) -> HashSet<Position> {
+        self.history(knot).iter().copied().collect()
     }
 
-    fn to_vec(&self) -> Vec<(i32, i32)> {
-        self.0.iter().map(|pos| (pos.0, pos.1)).collect_vec()
-        // let positions = knots.iter().map(|(x, y)| Position(*x, *y)).collect_vec();
-        // Rope(positions)
+    /// Picture of the rope as it is now, with its start and the `trail` of its tail.
+    fn board(&self, viewport: Viewport, trail: &HashSet<Position>) -> Board {
+        Board {
+            start: Some(Position(0, 0)),
+            trail: trail.clone(),
+            viewport,
+            ..Board::new(self.knots.clone())
+        }
     }
+}
+
+/// A rope moved one step at a time by movements, in place: yields the position of its tail
+/// before the first step and after every step, without keeping any, so that long inputs run in
+/// constant memory.
+struct Simulation<I> {
+    rope: Rope,
+    movements: I,
+    /// Offset and number of steps left of the movement in progress.
+    current: Option<(Position, usize)>,
+    started: bool,
+}
 
-    fn tail(&self) -> &Position {
-        self.0.last().unwrap()
+impl<I: Iterator<Item = Movement>> Simulation<I> {
+    fn new(rope: Rope, movements: impl IntoIterator<IntoIter = I>) -> Simulation<I> {
+        Simulation {
+            rope,
+            movements: movements.into_iter(),
+            current: None,
+            started: false,
+        }
     }
 
-    fn head(&self) -> &Position {
-        self.0.first().unwrap()
+    /// The rope as it is after the last step yielded.
+    fn rope(&self) -> &Rope {
+        &self.rope
     }
 }
 
+impl<I: Iterator<Item = Movement>> Iterator for Simulation<I> {
+    type Item = Position;
 
+    fn next(&mut self) -> Option<Position> {
+        if !self.started {
+            self.started = true;
+            return Some(self.rope.tail());
+        }
+        loop {
+            match &mut self.current {
+                Some((offset, steps)) if *steps > 0 => {
+                    *steps -= 1;
+                    let offset = *offset;
+                    self.rope.step(offset);
+                    return Some(self.rope.tail());
+                }
+                _ => {
+                    let movement = self.movements.next()?;
+                    self.current = Some((movement.direction.offset(), movement.size));
+                }
+            }
+        }
+    }
+}
 
-#[derive(PartialOrd, PartialEq, Debug)]
-enum Direction {
-    Right,
-    Left,
-    Up,
-    Down,
+/// Directions of the head, with their names in the input.
+const DIRECTIONS: [(Direction, &str); 8] = [
+    (Direction::Right, "R"),
+    (Direction::Left, "L"),
+    (Direction::Up, "U"),
+    (Direction::Down, "D"),
+    (Direction::UpRight, "UR"),
+    (Direction::UpLeft, "UL"),
+    (Direction::DownRight, "DR"),
+    (Direction::DownLeft, "DL"),
+];
+
+fn direction_name(direction: Direction) -> &'static str {
+    DIRECTIONS
+        .iter()
+        .find(|(known, _)| *known == direction)
+        .map(|(_, name)| *name)
+        .unwrap()
 }
 
-#[derive(PartialOrd, PartialEq, Debug)]
+#[derive(PartialEq, Debug, Copy, Clone)]
 struct Movement {
     direction: Direction,
     size: usize,
@@ -104,15 +224,7 @@ struct Movement {
 
 impl Display for Movement {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let c = match self.direction {
-            Direction::Right => 'R',
-            Direction::Left => 'L',
-            Direction::Up => 'U',
-            Direction::Down => 'D',
-        };
-        f.write_char(c)?;
-        f.write_char(' ')?;
-        f.write_str(self.size.to_string().as_str())
+        write!(f, "{} {}", direction_name(self.direction), self.size)
     }
 }
 
@@ -122,184 +234,199 @@ impl Movement {
     }
 }
 
-fn parse(lines: Vec<String>) -> Result<Vec<Movement>, String> {
-    let ret = lines
+fn parse_movement(line: &str) -> Result<Movement, ParseError> {
+    let (direction_char, size_char) = line
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new("expected a direction and a size, like 'R 4'"))?;
+    let Some((direction, _)) = DIRECTIONS
+        .into_iter()
+        .find(|(_, name)| *name == direction_char)
+    else {
+        let error = ParseError::new(format!("invalid direction '{direction_char}'"));
+        return Err(error.at(line, direction_char));
+    };
+    let size = number(line, size_char)?;
+    Ok(Movement::new(direction, size))
+}
+
+fn parse(lines: Vec<String>) -> Result<Vec<Movement>, ParseError> {
+    lines
         .iter()
-        .map(|line| {
-            let chars = line.split_ascii_whitespace().collect_vec();
-            let direction_char = chars[0];
-            let direction = match direction_char {
-                "R" => Direction::Right,
-                "U" => Direction::Up,
-                "D" => Direction::Down,
-                "L" => Direction::Left,
-                _ => return Err(format!("Invalid direction: {direction_char}")),
-            };
-            let size_char = chars[1];
-            let size: usize = size_char
-                .parse()
-                .map_err(|err| format!("Invalid size: {size_char}"))?;
-            return Ok(Movement { direction, size });
-        })
-        .try_collect()?;
-    Ok(ret)
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| parse_movement(line).on_line(i + 1, line))
+        .collect()
 }
 
-fn simulate(rope: &Rope, movement: &Movement) -> Vec<Rope> {
-    let mut ropes = Vec::with_capacity(movement.size);
-
-    // let mut file = open_for_append("day9-results.txt");
-    // append(&mut file,format!("{}", movement));
-    let mut intermediate_rope = rope.clone();
-    for _movement_i in 0..movement.size {
-        let mut factor_x = 0;
-        let mut factor_y = 0;
-        match movement.direction {
-            Direction::Right => factor_x = 1,
-            Direction::Left => factor_x = -1,
-            Direction::Up => factor_y = -1,
-            Direction::Down => factor_y = 1,
-        }
-        intermediate_rope.0[0] += (factor_x, factor_y);
-        for i in 0..intermediate_rope.0.len() - 1 {
-            let (head, tail) = get_mut2(&mut intermediate_rope.0, i, i + 1);
-
-            // Only move the tail if they're too distant
-            if !head.touching(tail) {
-                let df_x = (head.0 - tail.0).signum();
-                let df_y = (head.1 - tail.1).signum();
-                *tail += (df_x, df_y);
-            }
-        }
+fn inner(movements: &[Movement]) -> (PuzzleResult, PuzzleResult) {
+    let part1 = calculate_unique_tail_positions(movements, 2).len();
+    let part2 = calculate_unique_tail_positions(movements, 10).len();
+    (part1, part2)
+}
 
-        ropes.push(intermediate_rope.clone());
+fn calculate_unique_tail_positions(movements: &[Movement], rope_size: usize) -> HashSet<Position> {
+    Simulation::new(Rope::new(rope_size), movements.iter().copied()).collect()
+}
 
-        // append(&mut file, format!("{}\n", Board{ head, tail }));
-    }
-    ropes
+/// Area of the plane drawn by a [Board].
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub enum Viewport {
+    /// Just large enough for everything on the board.
+    Fit,
+    /// From `min` to `max` included, whatever is on the board, so that frames line up.
+    Fixed { min: Position, max: Position },
 }
 
-fn inner(lines: Vec<String>) -> (PuzzleResult, PuzzleResult) {
-    let movements = parse(lines).unwrap();
-    let part1 = calculate_unique_tail_positions(&movements, 2).len();
-    let part2 = calculate_unique_tail_positions(&movements, 10).len();
-    (part1, part2)
+/// Smallest rectangle containing all of `positions`, as its corners with the smallest and the
+/// largest coordinates.
+fn extent<'a>(positions: impl Iterator<Item = &'a Position> + Clone) -> (Position, Position) {
+    let (min_x, max_x) = positions
+        .clone()
+        .map(|position| position.0)
+        .minmax()
+        .into_option()
+        .unwrap_or((0, 0));
+    let (min_y, max_y) = positions
+        .map(|position| position.1)
+        .minmax()
+        .into_option()
+        .unwrap_or((0, 0));
+    (Position(min_x, min_y), Position(max_x, max_y))
 }
 
-fn calculate_unique_tail_positions(
-    movements: &Vec<Movement>,
-    rope_size: usize,
-) -> HashSet<Position> {
-    let mut unique_tail_positions = HashSet::new();
-    let mut rope = Rope::new(rope_size);
-    unique_tail_positions.insert(*rope.tail());
-    for movement in movements {
-        let intermediate_ropes = simulate(&rope, &movement);
-        rope = intermediate_ropes.last().unwrap().clone();
-        for intermediate_rope in intermediate_ropes.iter() {
-            unique_tail_positions.insert(*intermediate_rope.tail());
+/// Picture of a rope: its knots from the head `H` to the tail `T`, numbered in between, its start
+/// `s` and the positions its tail visited `#`. Rows go from the smallest y at the top to the
+/// largest one, as up is towards negative y, labelled with their y, and the first and last
+/// columns are labelled with their x.
+pub struct Board {
+    pub knots: Vec<Position>,
+    pub start: Option<Position>,
+    pub trail: HashSet<Position>,
+    pub viewport: Viewport,
+}
+
+impl Board {
+    pub fn new(knots: Vec<Position>) -> Board {
+        Board {
+            knots,
+            start: None,
+            trail: HashSet::new(),
+            viewport: Viewport::Fit,
         }
     }
-    unique_tail_positions
-}
 
-pub struct Board {
-    pub head: Position,
-    pub tail: Position,
+    fn bounds(&self) -> (Position, Position) {
+        match self.viewport {
+            Viewport::Fixed { min, max } => (min, max),
+            Viewport::Fit => extent(self.knots.iter().chain(&self.start).chain(&self.trail)),
+        }
+    }
+
+    /// What is drawn at `position`. Knots closer to the head are drawn over the others.
+    fn cell(&self, position: Position) -> char {
+        let tail = self.knots.len().saturating_sub(1);
+        match self.knots.iter().position(|knot| *knot == position) {
+            Some(0) => 'H',
+            Some(knot) if knot == tail => 'T',
+            Some(knot) => char::from_digit(knot as u32 % 10, 10).unwrap(),
+            None if self.start == Some(position) => 's',
+            None if self.trail.contains(&position) => '#',
+            None => '.',
+        }
+    }
 }
 
 impl Display for Board {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let head = (self.head.0, self.head.1);
-        let tail = (self.tail.0, self.tail.1);
-
-        let extra_size = 0;
-        let min_x = (head.0).min(tail.0) - extra_size;
-        let min_y = (head.1).min(tail.1) - extra_size;
-        let max_x = (head.0).max(tail.0) + extra_size;
-        let max_y = (head.1).max(tail.1) + extra_size;
-        let size_x = (head.0).abs_diff(tail.0) as usize;
-        let size_y = (head.1).abs_diff(tail.1) as usize;
-        let header = if size_x > 1 {
-            format!("|   {min_x:>3}{}{max_x}\n", " ".repeat(size_x - 1))
-        } else {
-            format!("|   {min_x:>3}\n")
-        };
-        f.write_str(header.as_str())?;
-        for y in (min_y..max_y + 1).rev() {
-            f.write_str(format!("| {y:>3} ").as_str())?;
-
-            for x in min_x..max_x + 1 {
-                if head == (x, y) {
-                    f.write_char('H')?;
-                } else if tail == (x, y) {
-                    f.write_char('T')?;
-                } else {
-                    f.write_char('.')?;
-                }
-            }
-            if y > min_y {
-                f.write_char('\n')?;
+        let (min, max) = self.bounds();
+        let label_width = [min.1, max.1]
+            .map(|y| y.to_string().len())
+            .into_iter()
+            .max()
+            .unwrap();
+        // Columns of the x labels are the columns of the cells they are over
+        let first_column = label_width + 3;
+        let columns = max.0.abs_diff(min.0) as usize;
+        let max_x = max.0.to_string();
+        write!(f, "|{:>first_column$}", min.0)?;
+        if columns > max_x.len() {
+            write!(f, "{max_x:>columns$}")?;
+        }
+        for y in min.1..max.1 + 1 {
+            write!(f, "\n| {y:>label_width$} ")?;
+            for x in min.0..max.0 + 1 {
+                f.write_char(self.cell(Position(x, y)))?;
             }
         }
         Ok(())
     }
 }
 
+/// Number of steps drawn by [write_frames]: enough for the examples, without writing gigabytes
+/// of frames for the puzzle input.
+const MAX_FRAMES: usize = 500;
+
+/// Draws a rope before the first step and after every step of `movements`, for comparing the
+/// simulation with the pictures of the puzzle.
+fn write_frames(
+    out: &mut impl io::Write,
+    movements: &[Movement],
+    rope_size: usize,
+    viewport: Viewport,
+) -> io::Result<()> {
+    let mut rope = Rope::new(rope_size).with_histories();
+    let tail = rope_size - 1;
+    writeln!(
+        out,
+        "== Initial State ==\n\n{}\n",
+        rope.board(viewport, &rope.visited(tail))
+    )?;
+    let mut frames = 0;
+    for movement in movements {
+        writeln!(out, "== {movement} ==\n")?;
+        for _ in 0..movement.size {
+            if frames == MAX_FRAMES {
+                return writeln!(out, "Stopped after {MAX_FRAMES} steps");
+            }
+            rope.step(movement.direction.offset());
+            writeln!(out, "{}\n", rope.board(viewport, &rope.visited(tail)))?;
+            frames += 1;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the frames of the 10-knot rope to `path`, all drawn over the area its first
+/// [MAX_FRAMES] steps cover so that the knots don't shift from one frame to the next.
+fn dump_frames(path: &Path, movements: &[Movement]) -> io::Result<()> {
+    let mut simulation = Simulation::new(Rope::new(10), movements.iter().copied());
+    let mut drawn = vec![];
+    for _ in 0..MAX_FRAMES + 1 {
+        if simulation.next().is_none() {
+            break;
+        }
+        drawn.extend_from_slice(simulation.rope().knots());
+    }
+    let (min, max) = extent(drawn.iter());
+    let mut out = BufWriter::new(File::create(path)?);
+    write_frames(&mut out, movements, 10, Viewport::Fixed { min, max })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::asserts;
+    use crate::examples::example_input;
     use crate::strings::SkipEmptyLines;
+    use expect_test::expect;
     use itertools::Itertools;
     use pretty_assertions::assert_eq as pretty_assert_eq;
     use textwrap::dedent;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    #[test]
-    fn test_part1_inner() -> Result<(), String> {
-        let movements_string = dedent(
-            "
-            R 4
-            U 4
-            L 3
-            D 1
-            R 4
-            D 1
-            L 5
-            R 2",
-        )
-        .skip_empty_start_lines();
-        let actual = inner(to_vec(movements_string.lines().collect_vec())).0;
-        assert_eq!(actual, 13);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_inner() -> Result<(), String> {
-        let movements_string = dedent(
-            "
-            R 5
-            U 8
-            L 8
-            D 3
-            R 17
-            D 10
-            L 25
-            U 20",
-        )
-        .skip_empty_start_lines();
-        let actual = inner(to_vec(movements_string.lines().collect_vec())).1;
-        assert_eq!(actual, 36);
-
-        Ok(())
-    }
+    crate::examples::example_tests!(2022, 9);
 
     #[test]
-    fn test_parse_movements() -> Result<(), String> {
+    fn test_parse_movements() -> Result<(), ParseError> {
         let movements_string = dedent(
             "
             R 4
@@ -309,7 +436,7 @@ mod tests {
             R 4",
         )
         .skip_empty_start_lines();
-        let lines = to_vec(movements_string.lines().collect_vec());
+        let lines = to_lines(&movements_string);
         let movements = parse(lines)?;
         assert_eq!(
             movements,
@@ -324,16 +451,50 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_movements_errors() {
+        let error = parse(to_lines("R 4\nX 4")).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: invalid direction 'X'\n  |\n2 | X 4\n  | ^"
+        );
+        let error = parse(to_lines("R 4\nU -1")).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(2), Some(3)));
+        let error = parse(to_lines("R4")).unwrap_err();
+        assert_eq!(error.line, Some(1));
+    }
+
+    /// Moves `rope` by `movement`, returning the positions of its knots after each step.
+    fn steps(rope: &mut Rope, movement: &Movement) -> Vec<Vec<Position>> {
+        (0..movement.size)
+            .map(|_| {
+                rope.step(movement.direction.offset());
+                rope.knots().to_vec()
+            })
+            .collect_vec()
+    }
+
+    fn knots_per_step(rope: &mut Rope, movement: &Movement) -> Vec<Vec<(i64, i64)>> {
+        steps(rope, movement)
+            .iter()
+            .map(|knots| knots.iter().map(|Position(x, y)| (*x, *y)).collect_vec())
+            .collect_vec()
+    }
+
     #[test]
     fn test_simulate_movements() {
-        let get_heads =
-            |ropes: &Vec<Rope>| ropes.iter().map(|rope| rope.head().clone()).collect_vec();
-        let get_tails =
-            |ropes: &Vec<Rope>| ropes.iter().map(|rope| rope.tail().clone()).collect_vec();
+        let mut rope = Rope::new(2);
+        let mut simulate = |movement: Movement| {
+            let steps = steps(&mut rope, &movement);
+            (
+                steps.iter().map(|knots| knots[0]).collect_vec(),
+                steps.iter().map(|knots| knots[1]).collect_vec(),
+            )
+        };
 
-        let ropes = simulate(&Rope::new(2), &Movement::new(Direction::Right, 4));
+        let (heads, tails) = simulate(Movement::new(Direction::Right, 4));
         assert_eq!(
-            get_heads(&ropes),
+            heads,
             vec![
                 Position(1, 0),
                 Position(2, 0),
@@ -342,7 +503,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            get_tails(&ropes),
+            tails,
             vec![
                 Position(0, 0),
                 Position(1, 0),
@@ -351,9 +512,9 @@ mod tests {
             ]
         );
 
-        let ropes = simulate(ropes.last().unwrap(), &Movement::new(Direction::Up, 4));
+        let (heads, tails) = simulate(Movement::new(Direction::Up, 4));
         assert_eq!(
-            get_heads(&ropes),
+            heads,
             vec![
                 Position(4, -1),
                 Position(4, -2),
@@ -362,7 +523,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            get_tails(&ropes),
+            tails,
             vec![
                 Position(3, 0),
                 Position(4, -1),
@@ -371,23 +532,23 @@ mod tests {
             ]
         );
 
-        let ropes = simulate(ropes.last().unwrap(), &Movement::new(Direction::Left, 3));
+        let (heads, tails) = simulate(Movement::new(Direction::Left, 3));
         assert_eq!(
-            get_heads(&ropes),
+            heads,
             vec![Position(3, -4), Position(2, -4), Position(1, -4),]
         );
         assert_eq!(
-            get_tails(&ropes),
+            tails,
             vec![Position(4, -3), Position(3, -4), Position(2, -4),]
         );
 
-        let ropes = simulate(ropes.last().unwrap(), &Movement::new(Direction::Down, 1));
-        assert_eq!(get_heads(&ropes), vec![Position(1, -3),]);
-        assert_eq!(get_tails(&ropes), vec![Position(2, -4),]);
+        let (heads, tails) = simulate(Movement::new(Direction::Down, 1));
+        assert_eq!(heads, vec![Position(1, -3),]);
+        assert_eq!(tails, vec![Position(2, -4),]);
 
-        let ropes = simulate(ropes.last().unwrap(), &Movement::new(Direction::Right, 4));
+        let (heads, tails) = simulate(Movement::new(Direction::Right, 4));
         assert_eq!(
-            get_heads(&ropes),
+            heads,
             vec![
                 Position(2, -3),
                 Position(3, -3),
@@ -396,7 +557,7 @@ mod tests {
             ]
         );
         assert_eq!(
-            get_tails(&ropes),
+            tails,
             vec![
                 Position(2, -4),
                 Position(2, -4),
@@ -405,21 +566,16 @@ mod tests {
             ]
         );
 
-        let ropes = simulate(ropes.last().unwrap(), &Movement::new(Direction::Down, 1));
-        assert_eq!(get_heads(&ropes), vec![Position(5, -2),]);
-        assert_eq!(get_tails(&ropes), vec![Position(4, -3),]);
+        let (heads, tails) = simulate(Movement::new(Direction::Down, 1));
+        assert_eq!(heads, vec![Position(5, -2),]);
+        assert_eq!(tails, vec![Position(4, -3),]);
     }
 
     #[test]
     fn test_simulate_movements_with_longer_rope() {
-        let get_heads =
-            |ropes: &Vec<Rope>| ropes.iter().map(|rope| rope.head().clone()).collect_vec();
-        let get_tails =
-            |ropes: &Vec<Rope>| ropes.iter().map(|rope| rope.tail().clone()).collect_vec();
-
-        let ropes = simulate(&Rope::new(4), &Movement::new(Direction::Right, 4));
+        let mut rope = Rope::new(4);
         assert_eq!(
-            ropes.iter().map(|r| r.to_vec()).collect_vec(),
+            knots_per_step(&mut rope, &Movement::new(Direction::Right, 4)),
             vec![
                 vec![(1, 0), (0, 0), (0, 0), (0, 0)],
                 vec![(2, 0), (1, 0), (0, 0), (0, 0)],
@@ -428,10 +584,9 @@ mod tests {
             ]
         );
 
-        let ropes = simulate(&ropes.last().unwrap(), &Movement::new(Direction::Down, 4));
         // pretty_assert_eq!(
         assert_eq!(
-            ropes.iter().map(|r| r.to_vec()).collect_vec(),
+            knots_per_step(&mut rope, &Movement::new(Direction::Down, 4)),
             vec![
                 vec![(4, 1), (3, 0), (2, 0), (1, 0)],
                 vec![(4, 2), (4, 1), (3, 1), (2, 1)],
@@ -442,14 +597,83 @@ mod tests {
     }
 
     #[test]
-    fn test_distance() {
-        assert_eq!(Position(0, 0).euclidean_distance(&Position(0, 0)), 0);
-        assert_eq!(Position(1, 0).euclidean_distance(&Position(0, 0)), 1);
-        assert_eq!(Position(1, 1).euclidean_distance(&Position(0, 0)), 1);
-        assert_eq!(Position(2, 0).euclidean_distance(&Position(0, 0)), 2);
-        assert_eq!(Position(2, 1).euclidean_distance(&Position(0, 0)), 2);
-        assert_eq!(Position(100, 1).euclidean_distance(&Position(0, 0)), 100);
-        assert_eq!(Position(100, 100).euclidean_distance(&Position(0, 0)), 141);
+    fn test_diagonal_moves() -> Result<(), ParseError> {
+        let movements = parse(to_lines("UR 2\nDL 1"))?;
+        assert_eq!(movements[0], Movement::new(Direction::UpRight, 2));
+        assert_eq!(movements[1].to_string(), "DL 1");
+
+        let mut rope = Rope::new(3);
+        assert_eq!(
+            movements
+                .iter()
+                .flat_map(|movement| knots_per_step(&mut rope, movement))
+                .collect_vec(),
+            vec![
+                vec![(1, -1), (0, 0), (0, 0)],
+                vec![(2, -2), (1, -1), (0, 0)],
+                vec![(1, -1), (1, -1), (0, 0)],
+            ]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_manhattan_rule() {
+        let mut rope = Rope::with_rule(3, FollowRule::Manhattan);
+        steps(&mut rope, &Movement::new(Direction::Right, 2));
+        assert_eq!(
+            rope.knots(),
+            [Position(2, 0), Position(1, 0), Position(0, 0)]
+        );
+        // Diagonal neighbours don't touch, knots catch up without moving diagonally
+        steps(&mut rope, &Movement::new(Direction::Up, 1));
+        assert_eq!(
+            rope.knots(),
+            [Position(2, -1), Position(2, 0), Position(1, 0)]
+        );
+        // A diagonal move of the head can take two steps to catch up
+        steps(&mut rope, &Movement::new(Direction::UpLeft, 1));
+        assert_eq!(
+            rope.knots(),
+            [Position(1, -2), Position(1, -1), Position(1, 0)]
+        );
+
+        let mut rope = Rope::new(3);
+        steps(&mut rope, &Movement::new(Direction::Right, 2));
+        steps(&mut rope, &Movement::new(Direction::Up, 1));
+        assert_eq!(
+            rope.knots(),
+            [Position(2, -1), Position(1, 0), Position(0, 0)]
+        );
+    }
+
+    #[test]
+    fn test_simulation() -> Result<(), ParseError> {
+        let movements = parse(to_lines(&example_input(2022, 9)))?;
+        let simulation = Simulation::new(Rope::new(10), movements.iter().copied());
+        assert_eq!(simulation.collect_vec(), vec![Position(0, 0); 25]);
+        let mut simulation = Simulation::new(Rope::new(10), movements.iter().copied());
+        simulation.by_ref().for_each(drop);
+        assert!(simulation.rope().histories.is_none());
+
+        let rope = Rope::new(10).with_histories();
+        let mut simulation = Simulation::new(rope, movements.iter().copied());
+        assert_eq!(simulation.by_ref().count(), 25);
+        let rope = simulation.rope();
+        // With the puzzle's rule, the second knot follows the head like the tail of a shorter rope
+        asserts::compare_sets(
+            &rope.visited(1),
+            &calculate_unique_tail_positions(&movements, 2),
+        );
+        assert_eq!(rope.visited(1).len(), 13);
+        asserts::compare_sets(&rope.visited(9), &HashSet::from([Position(0, 0)]));
+        assert_eq!(rope.history(0).len(), 25);
+        assert_eq!(rope.history(0)[24], Position(2, -2));
+
+        let mut simulation = Simulation::new(Rope::new(2), []);
+        assert_eq!(simulation.next(), Some(Position(0, 0)));
+        assert_eq!(simulation.next(), None);
+        Ok(())
     }
 
     #[test]
@@ -489,10 +713,7 @@ mod tests {
     #[test]
     fn test_format_board() {
         let board = |(head_x, head_y), (tail_x, tail_y)| {
-            let board = Board {
-                head: Position(head_x, head_y),
-                tail: Position(tail_x, tail_y),
-            };
+            let board = Board::new(vec![Position(head_x, head_y), Position(tail_x, tail_y)]);
             format!("{}", board)
         };
         pretty_assert_eq!(
@@ -527,8 +748,8 @@ mod tests {
             dedent(
                 "
              |   0 2
-             | 1 ..H
-             | 0 T.."
+             | 0 T..
+             | 1 ..H"
             )
             .skip_empty_start_lines()
         );
@@ -537,9 +758,9 @@ mod tests {
             dedent(
                 "
              |   0 2
-             | 2 ..H
+             | 0 T..
              | 1 ...
-             | 0 T.."
+             | 2 ..H"
             )
             .skip_empty_start_lines()
         );
@@ -548,9 +769,9 @@ mod tests {
             dedent(
                 "
              |  -1
-             | 2 H.
+             | 0 .T
              | 1 ..
-             | 0 .T"
+             | 2 H."
             )
             .skip_empty_start_lines()
         );
@@ -559,11 +780,69 @@ mod tests {
             dedent(
                 "
              |  -1  2
-             | 2 H...
+             | 0 ...T
              | 1 ....
-             | 0 ...T"
+             | 2 H..."
             )
             .skip_empty_start_lines()
         );
     }
+
+    #[test]
+    fn test_rope_board() {
+        let movements = [
+            Movement::new(Direction::Right, 5),
+            Movement::new(Direction::Up, 3),
+            Movement::new(Direction::Left, 1),
+        ];
+        let mut simulation = Simulation::new(Rope::new(4), movements);
+        let trail: HashSet<Position> = simulation.by_ref().collect();
+        let rope = simulation.rope();
+        expect![[r#"
+            |    0    5
+            | -3 ....H.
+            | -2 .....1
+            | -1 ...T2.
+            |  0 s##..."#]]
+        .assert_eq(&rope.board(Viewport::Fit, &trail).to_string());
+
+        let viewport = Viewport::Fixed {
+            min: Position(-1, -2),
+            max: Position(3, 1),
+        };
+        expect![[r#"
+            |   -1   3
+            | -2 .....
+            | -1 ....T
+            |  0 .s##.
+            |  1 ....."#]]
+        .assert_eq(&rope.board(viewport, &trail).to_string());
+    }
+
+    #[test]
+    fn test_write_frames() -> io::Result<()> {
+        let movements = [Movement {
+            direction: Direction::Right,
+            size: 2,
+        }];
+        let mut out = vec![];
+        write_frames(&mut out, &movements, 3, Viewport::Fit)?;
+        expect![[r#"
+            == Initial State ==
+
+            |   0
+            | 0 H
+
+            == R 2 ==
+
+            |   0
+            | 0 1H
+
+            |   0 2
+            | 0 T1H
+
+        "#]]
+        .assert_eq(&String::from_utf8(out).unwrap());
+        Ok(())
+    }
 }
@@ -1,3 +1,6 @@
+use crate::grammars::Grammar;
+use crate::registry::Solution;
+
 pub(crate) mod day1;
 pub(crate) mod day2;
 pub(crate) mod day3;
@@ -9,3 +12,25 @@ pub(crate) mod day8;
 pub(crate) mod day9;
 pub(crate) mod day10;
 pub(crate) mod day11;
+
+pub(crate) fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2022, 1, day1::solve),
+        Solution::new(2022, 2, day2::solve),
+        Solution::new(2022, 3, day3::solve),
+        Solution::new(2022, 4, day4::solve),
+        Solution::new(2022, 5, day5::solve),
+        Solution::new(2022, 6, day6::solve),
+        Solution::new(2022, 7, day7::solve),
+        Solution::new(2022, 8, day8::solve),
+        Solution::new(2022, 9, day9::solve),
+        Solution::new(2022, 10, day10::solve),
+        Solution::new(2022, 11, day11::solve),
+    ]
+}
+
+pub(crate) fn grammars() -> Vec<Grammar> {
+    vec![
+        Grammar::new(2022, 11, day11::parse_tree),
+    ]
+}
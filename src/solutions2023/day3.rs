@@ -1,5 +1,6 @@
-use crate::files::contents;
-use anyhow::{Context, Result};
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use anyhow::Result;
 use itertools::Itertools;
 use map_macro::hash_set;
 use ndarray::prelude::*;
@@ -11,11 +12,12 @@ use std::iter::zip;
 
 type PuzzleResult = u32;
 
-pub fn solution() -> (PuzzleResult, PuzzleResult) {
-    inner(&contents("inputs/2023/day3.txt")).unwrap()
+pub fn solve(input: &str) -> Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
 }
 
-fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
+fn inner(input: &str) -> Result<(PuzzleResult, PuzzleResult)> {
     let grid = parse(input)?;
     let adjacent_symbols = grid
         .symbols_with_adjacent_part_numbers();
@@ -39,6 +41,7 @@ fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
     Ok((part1, part2))
 }
 
+#[derive(Debug)]
 struct Grid {
     elems: Array2<i32>,
 }
@@ -115,9 +118,10 @@ enum ElementValue {
     Number(u32),
 }
 
-fn parse(input: &String) -> Result<Grid> {
+fn parse(input: &str) -> Result<Grid, ParseError> {
     let lines = input.lines().collect_vec();
-    let mut grid = Grid::new((lines.len(), lines[0].len()));
+    let width = lines.first().map_or(0, |line| line.len());
+    let mut grid = Grid::new((lines.len(), width));
     for (x, line) in lines.into_iter().enumerate() {
         // Split all elements together, while keeping numbers into their own group
         let (numbers, elements): (Vec<bool>, Vec<String>) = line.chars()
@@ -142,9 +146,8 @@ fn parse(input: &String) -> Result<Grid> {
 
             let value = match is_number {
                 true => {
-                    let parsed_value = elem.parse::<u32>().context(format!(
-                        "Couldn't parse '{elem}' from the line '{line}'"
-                    ))?;
+                    let token = &line[y..y + elem.len()];
+                    let parsed_value = number(line, token).on_line(x + 1, line)?;
                     ElementValue::Number(parsed_value)
                 },
                 false => {
@@ -165,54 +168,19 @@ fn parse(input: &String) -> Result<Grid> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::examples::example_input;
     use crate::strings::SkipEmptyLines;
-    use itertools::Itertools;
     use map_macro::hash_map;
     use nofmt;
     use pretty_assertions::assert_eq as pretty_assert_eq;
     use textwrap::dedent;
     use crate::asserts;
 
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    fn program() -> String {
-        return dedent(
-            "
-            467..114..
-            ...*......
-            ..35..633.
-            ......#...
-            617*......
-            .....+.58.
-            ..592.....
-            ......755.
-            ...$.*....
-            .664.598..",
-        )
-        .skip_empty_start_lines();
-    }
-    #[test]
-    fn test_part1_inner() -> Result<()> {
-        let input_string = program();
-        let actual = inner(&input_string)?.0;
-        assert_eq!(actual, 4361);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_inner() -> Result<()> {
-        let input_string = program();
-        let actual = inner(&input_string)?.1;
-        assert_eq!(actual, 467835);
-        Ok(())
-    }
+    crate::examples::example_tests!(2023, 3);
 
     #[test]
     fn test_parse() -> Result<()> {
-        let input_string = program();
+        let input_string = example_input(2023, 3);
         let grid = parse(&input_string)?;
         nofmt::pls! {
         let expected_array = array![
@@ -228,10 +196,19 @@ mod tests {
             [  0, 664, 664, 664,   0, 598, 598, 598,   0, 0],
         ];
         }
-        pretty_assert_eq!(grid.elems, expected_array);
+        asserts::compare_grids(&grid.elems, &expected_array);
         Ok(())
     }
 
+    #[test]
+    fn test_parse_error() {
+        let error = parse("...\n.*99999999999").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: invalid number '99999999999': number too large to fit in target type\n  |\n2 | .*99999999999\n  |   ^"
+        );
+    }
+
     #[test]
     fn test_parse_2() -> Result<()> {
         // let input_string = "....%..863..#......................36.............956..337%......692..............*744....$..........*......../.....187..-..................";
@@ -240,7 +217,7 @@ mod tests {
         let expected_array = array![[
             -42, 744, 744, 744, 0, 0, 456, 456, 456, -42, 0, 0, 123, 123, 123
         ]];
-        pretty_assert_eq!(grid.elems, expected_array);
+        asserts::compare_grids(&grid.elems, &expected_array);
         Ok(())
     }
 
@@ -258,7 +235,7 @@ mod tests {
             [0, 24, 24, 0, 0, 4, 0, 0],
             [0, 0, 0, 0, 0, 0, -42, 0]
         ]);
-        asserts::compare_maps(grid.symbols_with_adjacent_part_numbers(), hash_map![
+        asserts::compare_maps(&grid.symbols_with_adjacent_part_numbers(), &hash_map![
             ('*', (2, 6)) => hash_set![4],
         ]);
 
@@ -272,7 +249,7 @@ mod tests {
             [0, 24, 24, -36, -45, 4,   0, 0],
             [0,  0,  0,   0,   0, 0, -42, 0]
         ]);
-        asserts::compare_maps(grid.symbols_with_adjacent_part_numbers(), hash_map![
+        asserts::compare_maps(&grid.symbols_with_adjacent_part_numbers(), &hash_map![
             ('$', (1, 3)) => hash_set![24],
             ('-', (1, 4)) => hash_set![4],
             ('*', (2, 6)) => hash_set![4],
@@ -348,7 +325,7 @@ mod tests {
 
     #[test]
     fn test_symbols_with_adjacent_part_numbers() -> Result<()> {
-        let input_string = program();
+        let input_string = example_input(2023, 3);
         let grid = parse(&input_string)?;
         let actual = grid.symbols_with_adjacent_part_numbers();
         let expected = hash_map![
@@ -359,7 +336,7 @@ mod tests {
             ('$', (8, 3)) => hash_set![664],
             ('*', (8, 5)) => hash_set![598, 755],
         ];
-        asserts::compare_maps(actual, expected);
+        asserts::compare_maps(&actual, &expected);
         Ok(())
     }
 }
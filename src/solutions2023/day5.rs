@@ -1,8 +1,10 @@
-use crate::files::contents;
+use crate::geometry::{Interval, IntervalSet};
+use crate::parsing::{ParseError, ParseResult};
+use crate::registry::{answers, Answers};
+use crate::strings::{integers, integers_array, sections};
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
 use ndarray::prelude::*;
-use rayon::prelude::*;
 use std::cmp::min;
 use std::fmt::{Debug, Display, Write};
 use std::ops::Add;
@@ -12,11 +14,12 @@ use strum_macros::{Display, EnumCount, EnumIter};
 
 type PuzzleResult = usize;
 
-pub fn solution() -> (PuzzleResult, PuzzleResult) {
-    inner(&contents("inputs/2023/day5.txt")).unwrap()
+pub fn solve(input: &str) -> Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
 }
 
-fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
+fn inner(input: &str) -> Result<(PuzzleResult, PuzzleResult)> {
     let mut part1 = usize::MAX;
     let sources_part1 = parse(input, false)?;
     for seed in sources_part1.seeds.iter() {
@@ -24,16 +27,8 @@ fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
         part1 = min(part1, dest);
     }
 
-    let sources_part2 = parse(input, true)?;
-    let part2 = sources_part2
-        .seeds
-        .par_iter()
-        .map(|seed| {
-            let dest = sources_part2.map_seed(*seed)[Category::Location as usize];
-            dest
-        })
-        .min()
-        .unwrap();
+    let locations = sources_part1.map_seed_intervals(&sources_part1.seed_intervals());
+    let part2 = locations.min().context("No seeds to map")?;
 
     Ok((part1, part2))
 }
@@ -65,6 +60,10 @@ impl MappingRange {
             size,
         }
     }
+
+    fn source_interval(&self) -> Interval<usize> {
+        Interval::with_len(self.source, self.size)
+    }
 }
 
 #[derive(Debug, Default, PartialEq)]
@@ -75,16 +74,32 @@ struct Mapping {
 impl Mapping {
     pub(crate) fn map(&self, source: usize) -> usize {
         for range in self.ranges.iter() {
-            if source >= range.source && source < range.source + range.size {
+            if range.source_interval().contains(source) {
                 let distance = source - range.source;
                 return range.destination + distance;
             }
         }
         source
     }
+
+    /// Maps every value of `sources` at once, like [Mapping::map]: the first range covering a
+    /// value wins, and values not covered by any range map to themselves.
+    pub(crate) fn map_intervals(&self, sources: &IntervalSet<usize>) -> IntervalSet<usize> {
+        let mut unmapped = sources.clone();
+        let mut ret = IntervalSet::new();
+        for range in self.ranges.iter() {
+            let source_interval = range.source_interval();
+            for interval in unmapped.intersection_with(&source_interval).iter() {
+                let distance = interval.start - range.source;
+                ret.insert(interval.shifted_to(range.destination + distance));
+            }
+            unmapped.subtract(&source_interval);
+        }
+        ret.union(&unmapped)
+    }
 }
 
-#[derive(Default)]
+#[derive(Debug, Default)]
 struct Sources {
     mappings: [Mapping; Category::COUNT],
     seeds: Vec<usize>,
@@ -99,63 +114,64 @@ impl Sources {
             source
         })
     }
-}
 
-fn parse(input: &String, seed_ranges: bool) -> Result<Sources> {
-    let lines = input.lines().collect_vec();
+    /// The seeds read as `(start, length)` pairs, as part 2 wants.
+    pub(crate) fn seed_intervals(&self) -> IntervalSet<usize> {
+        self.seeds
+            .chunks(2)
+            .map(|chunk| Interval::with_len(chunk[0], chunk[1]))
+            .collect()
+    }
 
-    let seeds_components = lines[0]["seeds: ".len()..]
-        .split_ascii_whitespace()
-        .collect_vec();
+    pub(crate) fn map_seed_intervals(&self, seeds: &IntervalSet<usize>) -> IntervalSet<usize> {
+        self.mappings
+            .iter()
+            .fold(seeds.clone(), |sources, mapping| {
+                mapping.map_intervals(&sources)
+            })
+    }
+}
+
+fn parse(input: &str, seed_ranges: bool) -> Result<Sources, ParseError> {
+    let mut sections = sections(input);
+
+    let seeds_section = sections
+        .next()
+        .ok_or_else(|| ParseError::new("missing seeds"))?;
+    let header = seeds_section.header();
+    let line_number = seeds_section.start + 1;
+    let seeds_numbers = integers::<usize>(header).on_line(line_number, header)?;
+    if seed_ranges && seeds_numbers.len() % 2 != 0 {
+        let error = ParseError::new("seed ranges must be pairs of a start and a length");
+        return Err(error.on_line(line_number, header));
+    }
     let seeds: Vec<usize> = if seed_ranges {
-        seeds_components
+        seeds_numbers
             .chunks(2)
-            .map(|chunk| {
-                let (begin, size) = chunk
-                    .iter()
-                    .map(|num| num.parse::<usize>().unwrap())
-                    .collect_tuple()
-                    .unwrap();
-                begin..begin + size
-            })
-            .flatten()
+            .flat_map(|chunk| chunk[0]..chunk[0] + chunk[1])
             .collect()
     } else {
-        seeds_components
-            .iter()
-            .map(|num| num.parse::<usize>().unwrap())
-            .collect()
+        seeds_numbers
     };
 
-    let indices = lines
-        .iter()
-        .enumerate()
-        .filter_map(|(i, line)| if line.is_empty() { Some(i + 2) } else { None })
-        .collect_vec();
-    assert_eq!(indices.len(), Category::COUNT);
-
-    let mappings: [Mapping; Category::COUNT] = std::array::from_fn(|i| {
-        let begin = indices[i];
-        let end = if i >= indices.len() - 1 {
-            lines.len()
-        } else {
-            indices[i + 1] - 2
-        };
+    let mut mappings: Vec<Mapping> = vec![];
+    for section in sections {
         let mut ranges = vec![];
-        for i in begin..end {
-            let elems = lines[i]
-                .split_ascii_whitespace()
-                .map(|el| {
-                    el.parse::<usize>()
-                        .expect(format!("Failed to parse element {el}").as_str())
-                })
-                .collect_vec();
-            let mapping_range = MappingRange::new(elems[1], elems[0], elems[2]);
-            ranges.push(mapping_range);
+        for (i, line) in section.body().iter().enumerate() {
+            // The body starts right after the header
+            let line_number = section.start + i + 2;
+            let [destination, source, size] = integers_array(line).on_line(line_number, line)?;
+            ranges.push(MappingRange::new(source, destination, size));
         }
-
-        Mapping { ranges }
-    });
+        mappings.push(Mapping { ranges });
+    }
+    let found = mappings.len();
+    let mappings: [Mapping; Category::COUNT] = mappings.try_into().map_err(|_| {
+        ParseError::new(format!(
+            "expected {} mappings, found {found}",
+            Category::COUNT
+        ))
+    })?;
 
     let ret = Sources { seeds, mappings };
 
@@ -165,76 +181,17 @@ fn parse(input: &String, seed_ranges: bool) -> Result<Sources> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::strings::SkipEmptyLines;
-    use itertools::Itertools;
+    use crate::asserts;
+    use crate::examples::example_input;
     use map_macro::{hash_map, hash_set};
     use nofmt;
     use pretty_assertions::assert_eq as pretty_assert_eq;
-    use textwrap::dedent;
-
-    fn to_vec(f: Vec<&str>) -> Vec<String> {
-        f.into_iter().map(|s| String::from(s)).collect_vec()
-    }
-
-    fn program() -> String {
-        return dedent(
-            "
-            seeds: 79 14 55 13
-
-            seed-to-soil map:
-            50 98 2
-            52 50 48
 
-            soil-to-fertilizer map:
-            0 15 37
-            37 52 2
-            39 0 15
-
-            fertilizer-to-water map:
-            49 53 8
-            0 11 42
-            42 0 7
-            57 7 4
-
-            water-to-light map:
-            88 18 7
-            18 25 70
-
-            light-to-temperature map:
-            45 77 23
-            81 45 19
-            68 64 13
-
-            temperature-to-humidity map:
-            0 69 1
-            1 0 69
-
-            humidity-to-location map:
-            60 56 37
-            56 93 4",
-        )
-        .skip_empty_start_lines();
-    }
-    #[test]
-    fn test_part1_inner() -> Result<()> {
-        let input_string = program();
-        let actual = inner(&input_string)?.0;
-        assert_eq!(actual, 35);
-
-        Ok(())
-    }
-
-    #[test]
-    fn test_part2_inner() -> Result<()> {
-        let input_string = program();
-        let actual = inner(&input_string)?.1;
-        assert_eq!(actual, 46);
-        Ok(())
-    }
+    crate::examples::example_tests!(2023, 5);
 
     #[test]
     fn test_parse() -> Result<()> {
-        let input_string = program();
+        let input_string = example_input(2023, 5);
         let sources = parse(&input_string, false)?;
         assert_eq!(sources.seeds, vec![79, 14, 55, 13]);
         assert_eq!(
@@ -297,7 +254,7 @@ mod tests {
 
     #[test]
     fn test_parse_seed_ranges() -> Result<()> {
-        let input_string = program();
+        let input_string = example_input(2023, 5);
         let sources = parse(&input_string, true)?;
         assert_eq!(
             sources.seeds,
@@ -313,6 +270,25 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_parse_errors() {
+        let input = "seeds: 79 14 55\n\nseed-to-soil map:\n50 98 2";
+        let error = parse(input, true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1: seed ranges must be pairs of a start and a length\n  |\n1 | seeds: 79 14 55"
+        );
+        let error = parse(&format!("\n\n{input}"), true).unwrap_err();
+        assert_eq!(error.line, Some(3));
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50";
+        let error = parse(input, false).unwrap_err();
+        assert_eq!(error.message, "expected 3 integers, found 2");
+        assert_eq!(error.line, Some(5));
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2";
+        let error = parse(input, false).unwrap_err();
+        assert_eq!(error.to_string(), "expected 7 mappings, found 1");
+    }
+
     #[test]
     fn test_mapping() {
         let seed_to_soil = Mapping {
@@ -327,9 +303,38 @@ mod tests {
         assert_eq!(seed_to_soil.map(99), 51);
     }
 
+    #[test]
+    fn test_map_intervals() {
+        let seed_to_soil = Mapping {
+            ranges: vec![MappingRange::new(98, 50, 2), MappingRange::new(50, 52, 48)],
+        };
+        let seeds: IntervalSet<usize> = [Interval::new(40, 60), Interval::new(97, 102)]
+            .into_iter()
+            .collect();
+        // 40..50 and 100..102 are unmapped, 50..60 moves to 52..62, 97 to 99 and 98..100 to 50..52
+        asserts::compare_vecs(
+            seed_to_soil.map_intervals(&seeds).intervals(),
+            &[Interval::new(40, 62), Interval::new(99, 102)],
+        );
+
+        // 15..20 is covered by both ranges, only the first one maps it
+        let overlapping = Mapping {
+            ranges: vec![
+                MappingRange::new(10, 100, 10),
+                MappingRange::new(15, 200, 10),
+            ],
+        };
+        let seeds: IntervalSet<usize> = [Interval::new(10, 25)].into_iter().collect();
+        asserts::compare_vecs(
+            overlapping.map_intervals(&seeds).intervals(),
+            &[Interval::new(100, 110), Interval::new(205, 210)],
+        );
+        assert_eq!(overlapping.map(17), 107);
+    }
+
     #[test]
     fn test_all_mappings() -> Result<()> {
-        let input_string = program();
+        let input_string = example_input(2023, 5);
         let sources = parse(&input_string, false)?;
         assert_eq!(sources.map_seed(79), [81, 81, 81, 74, 78, 78, 82]);
         assert_eq!(sources.map_seed(14), [14, 53, 49, 42, 42, 43, 43]);
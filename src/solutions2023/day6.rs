@@ -1,6 +1,8 @@
-use crate::files::contents;
+use crate::parsing::{number, ParseError, ParseResult};
+use crate::registry::{answers, Answers};
 use anyhow::{bail, Context, Result};
 use itertools::Itertools;
+use log::trace;
 use ndarray::prelude::*;
 use rayon::prelude::*;
 use std::cmp::min;
@@ -13,11 +15,12 @@ use strum_macros::{Display, EnumCount, EnumIter};
 
 type PuzzleResult = usize;
 
-pub fn solution() -> (PuzzleResult, PuzzleResult) {
-    inner(&contents("inputs/2023/day6.txt")).unwrap()
+pub fn solve(input: &str) -> Result<Answers> {
+    let (part1, part2) = inner(input)?;
+    Ok(answers(part1, part2))
 }
 
-fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
+fn inner(input: &str) -> Result<(PuzzleResult, PuzzleResult)> {
     fn solve(races: &Vec<Race>) -> PuzzleResult {
         races.iter()
             .map(|race| {
@@ -33,7 +36,11 @@ fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
                     1.
                 };
 
-                dbg!(dbg!(max_time.floor()) - dbg!(min_time.ceil()) + dbg!(offset))
+                let ways = max_time.floor() - min_time.ceil() + offset;
+                trace!(
+                    "time {t}, distance {d}: holding from {min_time} to {max_time}, {ways} ways"
+                );
+                ways
             })
             .product::<f64>() as PuzzleResult
     }
@@ -46,32 +53,38 @@ fn inner(input: &String) -> Result<(PuzzleResult, PuzzleResult)> {
     Ok((part1, part2))
 }
 
+#[derive(Debug)]
 struct Race {
     time: usize,
     distance: usize,
 }
 
-fn parse(input: &String, multiple_races: bool) -> Result<Vec<Race>> {
-    let lines = input.lines().collect_vec();
-    let parse_nums = |line: &str| -> Result<Vec<usize>> {
-        let ret = if multiple_races {
-            line
-                .trim()
-                .split_ascii_whitespace()
-                .map(|elem| elem.trim().parse::<usize>())
-                .try_collect()?
-        } else {
-            vec![line
-                .trim()
-                .chars()
-                .filter(|c| *c != ' ')
-                .join("")
-                .trim().parse::<usize>()?]
-        };
-        Ok(ret)
+fn parse_nums(line: &str, label: &str, multiple_races: bool) -> Result<Vec<usize>, ParseError> {
+    let nums = line
+        .strip_prefix(label)
+        .ok_or_else(|| ParseError::new(format!("expected a line starting with '{label}'")))?;
+    let ret = if multiple_races {
+        nums
+            .split_ascii_whitespace()
+            .map(|elem| number(line, elem))
+            .try_collect()?
+    } else {
+        // Not a part of the line, so errors can't point at it
+        let digits = nums.chars().filter(|c| *c != ' ').join("");
+        vec![number(line, &digits)?]
     };
-    let times = parse_nums(&lines[0]["Time:".len()..])?;
-    let distances = parse_nums(&lines[1]["Distance:".len()..])?;
+    Ok(ret)
+}
+
+fn parse(input: &str, multiple_races: bool) -> Result<Vec<Race>, ParseError> {
+    let lines = input.lines().collect_vec();
+    let line = |i: usize| lines.get(i).copied().unwrap_or_default();
+    let times = parse_nums(line(0), "Time:", multiple_races).on_line(1, line(0))?;
+    let distances = parse_nums(line(1), "Distance:", multiple_races).on_line(2, line(1))?;
+    if times.len() != distances.len() {
+        let error = ParseError::new(format!("expected {} distances, one per time", times.len()));
+        return Err(error.on_line(2, line(1)));
+    }
     let ret = zip(times, distances)
         .map(|(time, distance)| Race { time, distance })
         .collect_vec();
@@ -81,32 +94,19 @@ fn parse(input: &String, multiple_races: bool) -> Result<Vec<Race>> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::strings::SkipEmptyLines;
-    use nofmt;
-    use textwrap::dedent;
 
-    fn program() -> String {
-        return dedent(
-            "
-            Time:      7  15   30
-            Distance:  9  40  200",
-        )
-        .skip_empty_start_lines();
-    }
-    #[test]
-    fn test_part1_inner() -> Result<()> {
-        let input_string = program();
-        let actual = inner(&input_string)?.0;
-        assert_eq!(actual, 288);
-
-        Ok(())
-    }
+    crate::examples::example_tests!(2023, 6);
 
     #[test]
-    fn test_part2_inner() -> Result<()> {
-        let input_string = program();
-        let actual = inner(&input_string)?.1;
-        assert_eq!(actual, 71503);
-        Ok(())
+    fn test_parse_errors() {
+        let error = parse("Time: 7 15\nDistance: 9", true).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2: expected 2 distances, one per time\n  |\n2 | Distance: 9"
+        );
+        let error = parse("Time: 7 1x\nDistance: 9 40", true).unwrap_err();
+        assert_eq!((error.line, error.column), (Some(1), Some(9)));
+        let error = parse("Time: 7", false).unwrap_err();
+        assert_eq!(error.message, "expected a line starting with 'Distance:'");
     }
 }
@@ -1,6 +1,26 @@
+use crate::grammars::Grammar;
+use crate::registry::Solution;
+
 pub(crate) mod day1;
 pub(crate) mod day2;
 pub(crate) mod day3;
 pub(crate) mod day4;
 pub(crate) mod day5;
 pub(crate) mod day6;
+
+pub(crate) fn solutions() -> Vec<Solution> {
+    vec![
+        Solution::new(2023, 1, day1::solve),
+        Solution::new(2023, 2, day2::solve),
+        Solution::new(2023, 3, day3::solve),
+        Solution::new(2023, 4, day4::solve),
+        Solution::new(2023, 5, day5::solve),
+        Solution::new(2023, 6, day6::solve),
+    ]
+}
+
+pub(crate) fn grammars() -> Vec<Grammar> {
+    vec![
+        Grammar::new(2023, 2, day2::parse_tree),
+    ]
+}
@@ -1,97 +1,567 @@
-use crate::geometry::overlaps;
 use itertools::Itertools;
-use map_macro::hash_map;
-use std::collections::{HashMap, HashSet};
-use std::iter::zip;
+use num::PrimInt;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::num::ParseIntError;
+use std::str::FromStr;
 
-pub fn common_char(strings: &Vec<&str>) -> char {
+/// Characters present in every one of `strings`, sorted. Empty when there are no strings.
+pub fn common_chars<S: AsRef<str>>(strings: &[S]) -> Vec<char> {
+    let mut sets = strings
+        .iter()
+        .map(|s| s.as_ref().chars().collect::<HashSet<char>>());
+    let Some(first) = sets.next() else {
+        return vec![];
+    };
+    sets.fold(first, |left, right| {
+        left.intersection(&right).copied().collect()
+    })
+    .into_iter()
+    .sorted()
+    .collect_vec()
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum CommonCharError {
+    NoStrings,
+    NoCommonChar,
+    MultipleCommonChars(Vec<char>),
+}
+
+impl Display for CommonCharError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CommonCharError::NoStrings => write!(f, "Need at least one string"),
+            CommonCharError::NoCommonChar => write!(f, "No common chars found"),
+            CommonCharError::MultipleCommonChars(chars) => {
+                write!(f, "Expected a single common char, found {chars:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CommonCharError {}
+
+/// The single character present in every one of `strings`.
+pub fn common_char<S: AsRef<str>>(strings: &[S]) -> Result<char, CommonCharError> {
     if strings.is_empty() {
-        panic!("need at least one element");
+        return Err(CommonCharError::NoStrings);
     }
-    let sets: Vec<HashSet<char>> = strings
-        .iter()
-        .map(|s| HashSet::from_iter(s.chars()))
-        .collect();
-    let mut sets_iter = sets.iter();
+    let chars = common_chars(strings);
+    match chars.as_slice() {
+        [] => Err(CommonCharError::NoCommonChar),
+        [c] => Ok(*c),
+        _ => Err(CommonCharError::MultipleCommonChars(chars)),
+    }
+}
 
-    let common_chars = sets_iter.next().map(|first| {
-        sets_iter.fold(first.clone(), |left, right| {
-            left.intersection(&right).cloned().collect()
-        })
-    });
-    let chars = common_chars.expect("Should never happen. The `if` from top ensures this!");
-    if chars.is_empty() {
-        panic!("No common chars found for strings: {strings:?}")
+/// Position of an ASCII letter in `a..=z` followed by `A..=Z`, which is how [LetterSet] stores it.
+pub fn letter_index(c: char) -> Option<u32> {
+    match c {
+        'a'..='z' => Some(c as u32 - 'a' as u32),
+        'A'..='Z' => Some(c as u32 - 'A' as u32 + 26),
+        _ => None,
+    }
+}
+
+fn letter_from_index(index: u32) -> char {
+    if index < 26 {
+        (b'a' + index as u8) as char
+    } else {
+        (b'A' + (index - 26) as u8) as char
     }
-    // HACK: Returning a single char
-    *chars.iter().exactly_one().unwrap()
 }
 
+/// Set of ASCII letters stored as a 52-bit mask, ordered as in [letter_index].
+#[derive(Eq, PartialEq, Hash, Debug, Copy, Clone, Default)]
+pub struct LetterSet(u64);
+
+impl LetterSet {
+    pub fn new() -> LetterSet {
+        LetterSet(0)
+    }
+
+    /// Letters of `s`. Returns `None` if `s` has anything other than ASCII letters.
+    pub fn from_letters(s: &str) -> Option<LetterSet> {
+        let mut ret = LetterSet::new();
+        for c in s.chars() {
+            ret.0 |= 1 << letter_index(c)?;
+        }
+        Some(ret)
+    }
+
+    /// Returns `false` if `c` is not an ASCII letter.
+    pub fn insert(&mut self, c: char) -> bool {
+        match letter_index(c) {
+            Some(index) => {
+                self.0 |= 1 << index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn contains(&self, c: char) -> bool {
+        letter_index(c).is_some_and(|index| self.0 & (1 << index) != 0)
+    }
+
+    pub fn intersection(&self, other: &LetterSet) -> LetterSet {
+        LetterSet(self.0 & other.0)
+    }
+
+    pub fn union(&self, other: &LetterSet) -> LetterSet {
+        LetterSet(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The letters, in [letter_index] order.
+    pub fn chars(&self) -> impl Iterator<Item = char> {
+        let bits = self.0;
+        (0..52)
+            .filter(move |index| bits & (1 << index) != 0)
+            .map(letter_from_index)
+    }
+}
+
+/// Letters present in every one of `strings`. Returns `None` if any string has something other
+/// than ASCII letters.
+pub fn common_letters<S: AsRef<str>>(strings: &[S]) -> Option<LetterSet> {
+    let mut sets = strings.iter().map(|s| LetterSet::from_letters(s.as_ref()));
+    let first = sets.next().unwrap_or(Some(LetterSet::new()))?;
+    sets.try_fold(first, |acc, set| Some(acc.intersection(&set?)))
+}
+
+/// Byte indices where each pattern starts, including overlapping occurrences.
 pub fn find_indices<const N: usize>(string: &str, patterns: &[&str; N]) -> [Vec<usize>; N] {
-    std::array::from_fn(|i| {
-        string
-            .match_indices(patterns[i])
-            .map(|(index, s)| index)
-            .collect_vec()
+    let mut ret: [Vec<usize>; N] = std::array::from_fn(|_| vec![]);
+    let matcher = MultiPatternMatcher::new(patterns);
+    for m in matcher.find_overlapping(string).sorted_by_key(|m| m.start) {
+        ret[m.pattern].push(m.start);
+    }
+    ret
+}
+
+/// An occurrence of the pattern with index `pattern` at the bytes `start..end` of the haystack.
+#[derive(Eq, PartialEq, Debug, Copy, Clone)]
+pub struct Match {
+    pub pattern: usize,
+    pub start: usize,
+    pub end: usize,
+}
+
+#[derive(Default)]
+struct TrieNode {
+    children: HashMap<u8, usize>,
+    fail: usize,
+    // Patterns ending at this node, including the ones reachable through `fail`
+    outputs: Vec<usize>,
+}
+
+/// Aho–Corasick automaton: finds every occurrence of many patterns in a single pass over the
+/// haystack, overlapping ones included (`"twone"` has both `"two"` and `"one"`).
+pub struct MultiPatternMatcher {
+    nodes: Vec<TrieNode>,
+    pattern_lengths: Vec<usize>,
+}
+
+impl MultiPatternMatcher {
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> MultiPatternMatcher {
+        let mut nodes = vec![TrieNode::default()];
+        for (pattern_index, pattern) in patterns.iter().enumerate() {
+            // Left out of the trie, as the root and every node inheriting its outputs would match
+            // an empty pattern everywhere
+            if pattern.as_ref().is_empty() {
+                continue;
+            }
+            let mut current = 0;
+            for byte in pattern.as_ref().bytes() {
+                current = match nodes[current].children.get(&byte) {
+                    Some(next) => *next,
+                    None => {
+                        nodes.push(TrieNode::default());
+                        let next = nodes.len() - 1;
+                        nodes[current].children.insert(byte, next);
+                        next
+                    }
+                };
+            }
+            nodes[current].outputs.push(pattern_index);
+        }
+
+        // Breadth first, so the fail link of a node's parent is always ready
+        let mut queue: VecDeque<usize> = nodes[0].children.values().copied().collect();
+        while let Some(current) = queue.pop_front() {
+            let children = nodes[current]
+                .children
+                .iter()
+                .map(|(byte, child)| (*byte, *child))
+                .collect_vec();
+            for (byte, child) in children {
+                let mut fail = nodes[current].fail;
+                let child_fail = loop {
+                    if let Some(next) = nodes[fail].children.get(&byte) {
+                        break *next;
+                    }
+                    if fail == 0 {
+                        break 0;
+                    }
+                    fail = nodes[fail].fail;
+                };
+                nodes[child].fail = child_fail;
+                let inherited = nodes[child_fail].outputs.clone();
+                nodes[child].outputs.extend(inherited);
+                queue.push_back(child);
+            }
+        }
+
+        let pattern_lengths = patterns.iter().map(|p| p.as_ref().len()).collect_vec();
+        MultiPatternMatcher {
+            nodes,
+            pattern_lengths,
+        }
+    }
+
+    /// Every match in `haystack`, ordered by where they end. Empty patterns never match.
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a str) -> impl Iterator<Item = Match> + 'a {
+        let mut current = 0;
+        haystack.bytes().enumerate().flat_map(move |(i, byte)| {
+            current = loop {
+                if let Some(next) = self.nodes[current].children.get(&byte) {
+                    break *next;
+                }
+                if current == 0 {
+                    break 0;
+                }
+                current = self.nodes[current].fail;
+            };
+            self.nodes[current]
+                .outputs
+                .iter()
+                .map(move |pattern| Match {
+                    pattern: *pattern,
+                    start: i + 1 - self.pattern_lengths[*pattern],
+                    end: i + 1,
+                })
+        })
+    }
+}
+
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub enum IntegersErrorKind {
+    /// The digits don't fit in the requested type.
+    Invalid {
+        text: String,
+        source: ParseIntError,
+    },
+    WrongCount {
+        expected: usize,
+        found: usize,
+    },
+}
+
+/// Error extracting integers from `line`. `line_number` (1-based) is known when the line was
+/// taken from a whole input, see [IntegersError::with_line_number].
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct IntegersError {
+    pub line_number: Option<usize>,
+    pub line: String,
+    pub kind: IntegersErrorKind,
+}
+
+impl IntegersError {
+    pub fn with_line_number(self, line_number: usize) -> IntegersError {
+        IntegersError {
+            line_number: Some(line_number),
+            ..self
+        }
+    }
+}
+
+impl Display for IntegersError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if let Some(line_number) = self.line_number {
+            write!(f, "Line {line_number}: ")?;
+        }
+        match &self.kind {
+            IntegersErrorKind::Invalid { text, source } => {
+                write!(f, "invalid integer '{text}' ({source})")?
+            }
+            IntegersErrorKind::WrongCount { expected, found } => {
+                write!(f, "expected {expected} integers, found {found}")?
+            }
+        }
+        write!(f, " in '{}'", self.line)
+    }
+}
+
+impl std::error::Error for IntegersError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match &self.kind {
+            IntegersErrorKind::Invalid { source, .. } => Some(source),
+            IntegersErrorKind::WrongCount { .. } => None,
+        }
+    }
+}
+
+/// Every integer in `line`, ignoring anything else. A `-` right before the digits is only taken
+/// as a sign for signed types, so `integers::<u32>("2-4")` is `[2, 4]` while
+/// `integers::<i32>("x=-3")` is `[-3]`.
+pub fn integers<T>(line: &str) -> Result<Vec<T>, IntegersError>
+where
+    T: PrimInt + FromStr<Err = ParseIntError>,
+{
+    let signed = T::min_value() < T::zero();
+    let bytes = line.as_bytes();
+    let mut ret = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+        let start = if signed && i > 0 && bytes[i - 1] == b'-' {
+            i - 1
+        } else {
+            i
+        };
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+        let text = &line[start..i];
+        let value = text.parse::<T>().map_err(|source| IntegersError {
+            line_number: None,
+            line: line.to_string(),
+            kind: IntegersErrorKind::Invalid {
+                text: text.to_string(),
+                source,
+            },
+        })?;
+        ret.push(value);
+    }
+    Ok(ret)
+}
+
+/// Same as [integers], but the line must have exactly `N` of them.
+pub fn integers_array<T, const N: usize>(line: &str) -> Result<[T; N], IntegersError>
+where
+    T: PrimInt + FromStr<Err = ParseIntError>,
+{
+    let values = integers::<T>(line)?;
+    let found = values.len();
+    values.try_into().map_err(|_| IntegersError {
+        line_number: None,
+        line: line.to_string(),
+        kind: IntegersErrorKind::WrongCount { expected: N, found },
     })
 }
 
+/// [integers] of every line of `input`, with line numbers in the errors.
+pub fn integers_per_line<T>(input: &str) -> Result<Vec<Vec<T>>, IntegersError>
+where
+    T: PrimInt + FromStr<Err = ParseIntError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| integers(line).map_err(|err| err.with_line_number(i + 1)))
+        .collect()
+}
+
+/// Owned lines of `input`, split on `\n` so a trailing newline yields a final empty line, the
+/// same way [crate::files::lines] reads input files.
+pub fn to_lines(input: &str) -> Vec<String> {
+    input.split('\n').map(String::from).collect_vec()
+}
+
+fn is_blank(line: &str) -> bool {
+    line.trim().is_empty()
+}
+
 pub trait SkipEmptyLines {
     fn skip_empty_start_lines(&self) -> Self;
+    fn skip_empty_end_lines(&self) -> Self;
+
+    /// Removes blank lines from both the start and the end.
+    fn trim_empty_lines(&self) -> Self
+    where
+        Self: Sized,
+    {
+        self.skip_empty_start_lines().skip_empty_end_lines()
+    }
 }
 
 impl SkipEmptyLines for String {
     fn skip_empty_start_lines(&self) -> Self {
-        self.lines().skip_while(|s| s.trim().is_empty()).join("\n")
+        self.lines().skip_while(|s| is_blank(s)).join("\n")
+    }
+
+    fn skip_empty_end_lines(&self) -> Self {
+        self.lines().collect_vec().skip_empty_end_lines().join("\n")
     }
 }
 
 impl SkipEmptyLines for Vec<&str> {
     fn skip_empty_start_lines(&self) -> Self {
-        let mut r: Vec<&str> = Vec::with_capacity(self.capacity());
-        for elem in self {
-            r.push(elem);
+        self.iter()
+            .copied()
+            .skip_while(|s| is_blank(s))
+            .collect_vec()
+    }
+
+    fn skip_empty_end_lines(&self) -> Self {
+        let end = self
+            .iter()
+            .rposition(|s| !is_blank(s))
+            .map_or(0, |last| last + 1);
+        self[..end].to_vec()
+    }
+}
+
+/// Removes the indentation common to all non-blank lines, and the blank lines around the text.
+/// Lines with only whitespace become empty. Indentation is counted in characters, whatever
+/// whitespace they are.
+pub fn dedent(s: &str) -> String {
+    let indentation = s
+        .lines()
+        .filter(|line| !is_blank(line))
+        .map(|line| line.chars().take_while(|c| c.is_whitespace()).count())
+        .min()
+        .unwrap_or(0);
+    s.lines()
+        .map(|line| {
+            if is_blank(line) {
+                ""
+            } else {
+                let offset = line.char_indices().nth(indentation).map_or(0, |(i, _)| i);
+                &line[offset..]
+            }
+        })
+        .collect_vec()
+        .trim_empty_lines()
+        .join("\n")
+}
+
+/// A group of consecutive non-blank lines, as returned by [sections].
+#[derive(Eq, PartialEq, Debug, Clone)]
+pub struct Section<'a> {
+    /// Index of the section's first line in the whole input (0-based).
+    pub start: usize,
+    lines: Vec<&'a str>,
+}
+
+impl<'a> Section<'a> {
+    pub fn lines(&self) -> &[&'a str] {
+        &self.lines
+    }
+
+    /// The first line, e.g. `seed-to-soil map:` in 2023 day5.
+    pub fn header(&self) -> &'a str {
+        self.lines[0]
+    }
+
+    /// Every line after the header.
+    pub fn body(&self) -> &[&'a str] {
+        &self.lines[1..]
+    }
+
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+}
+
+/// Iterator over the blank-line separated sections of an input.
+pub struct Sections<'a> {
+    lines: std::iter::Enumerate<std::str::Lines<'a>>,
+}
+
+impl<'a> Iterator for Sections<'a> {
+    type Item = Section<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (start, first) = self.lines.find(|(_, line)| !is_blank(line))?;
+        let mut lines = vec![first];
+        for (_, line) in self.lines.by_ref() {
+            if is_blank(line) {
+                break;
+            }
+            lines.push(line);
         }
-        return r;
-    }
-}
-
-// fn replace_from_left(s: &str, replacements: HashMap<&str, &str>) -> String {
-//     let indices = find_indices(
-//         &line,
-//         &[
-//             "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-//         ],
-//     );
-//     let replacements = vec!["0", "1", "2", "3", "4", "5", "6", "7", "8", "9"];
-//     let indices_sorted = zip(indices, replacements)
-//         .filter(||(index, replacement)|)
-//         .filter_map(|(index, replacement)| index.map(|i| (i, replacement)))
-//         .sorted_by_key(|(index, replacement)| *index)
-//         .collect_vec();
-//     if indices_sorted.is_empty() {
-//         return String::from(s);
-//     }
-//
-//     let mut indices_to_use = vec![];
-//     let mut max_i = -1;
-//     for elem in indices_sorted {
-//         if elem.0 < max_i {
-//             indices_to_use.push(elem);
-//             max_i = elem.0 + elem.1.len()
-//         }
-//     }
-//     // Now finally reassemble the string
-//     indices_to_use.map(|(index, replacement)| {
-//
-//     });
-//     "".to_string()
-// }
+        Some(Section { start, lines })
+    }
+}
+
+/// Splits `input` into sections separated by one or more blank lines. Blank lines at the start
+/// and end are ignored.
+pub fn sections(input: &str) -> Sections<'_> {
+    Sections {
+        lines: input.lines().enumerate(),
+    }
+}
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use map_macro::hash_map;
+
+    #[test]
+    fn test_common_chars() {
+        assert_eq!(common_chars(&["abcd", "dcx", "xcd"]), vec!['c', 'd']);
+        assert_eq!(common_chars(&["ab", "cd"]), Vec::<char>::new());
+        assert_eq!(common_chars::<&str>(&[]), Vec::<char>::new());
+    }
+
+    #[test]
+    fn test_common_char() {
+        assert_eq!(common_char(&["vJrwpWtwJgWr", "hcsFMMfFFhFp"]), Ok('p'));
+        assert_eq!(
+            common_char(&[String::from("abc"), String::from("cba")]),
+            Err(CommonCharError::MultipleCommonChars(vec!['a', 'b', 'c']))
+        );
+        assert_eq!(
+            common_char(&["ab", "cd"]),
+            Err(CommonCharError::NoCommonChar)
+        );
+        assert_eq!(common_char::<&str>(&[]), Err(CommonCharError::NoStrings));
+    }
+
+    #[test]
+    fn test_letter_set() {
+        assert_eq!(letter_index('a'), Some(0));
+        assert_eq!(letter_index('z'), Some(25));
+        assert_eq!(letter_index('A'), Some(26));
+        assert_eq!(letter_index('Z'), Some(51));
+        assert_eq!(letter_index('1'), None);
+
+        let set = LetterSet::from_letters("zZaAa").unwrap();
+        assert_eq!(set.len(), 4);
+        assert!(set.contains('Z'));
+        assert!(!set.contains('b'));
+        assert_eq!(set.chars().collect_vec(), vec!['a', 'z', 'A', 'Z']);
+        assert_eq!(LetterSet::from_letters("ab1"), None);
+
+        let mut set = LetterSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert('q'));
+        assert!(!set.insert('?'));
+        assert_eq!(set.chars().collect_vec(), vec!['q']);
+    }
+
+    #[test]
+    fn test_common_letters() {
+        let common = common_letters(&[
+            "vJrwpWtwJgWrhcsFMMfFFhFp",
+            "jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL",
+            "PmmdzqPrVvPwwTWBwg",
+        ]);
+        assert_eq!(common.map(|set| set.chars().collect_vec()), Some(vec!['r']));
+        assert_eq!(common_letters(&["ab", "a-"]), None);
+    }
 
     #[test]
     fn test_find_indices() {
@@ -109,16 +579,175 @@ mod tests {
         );
     }
 
-    // #[test]
-    // fn test_replace_from_left() {
-    //     assert_eq!(replace_from_left("onetwo", hash_map![
-    //         "one" => "1",
-    //         "two" => "2",
-    //     ]), "12");
-    //     assert_eq!(replace_from_left("eightwo three", hash_map![
-    //         "eight" => "8",
-    //         "two" => "2",
-    //         "three" => "3",
-    //     ]), "8wo 3");
-    // }
+    #[test]
+    fn test_find_indices_overlapping() {
+        assert_eq!(
+            find_indices("aaaa", &["aa", "a"]),
+            [vec![0, 1, 2], vec![0, 1, 2, 3]]
+        );
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher() {
+        let matcher = MultiPatternMatcher::new(&["one", "two", "eight", "ne"]);
+        let matches = matcher.find_overlapping("xtwoneighthree").collect_vec();
+        assert_eq!(
+            matches,
+            vec![
+                Match {
+                    pattern: 1,
+                    start: 1,
+                    end: 4
+                },
+                Match {
+                    pattern: 0,
+                    start: 3,
+                    end: 6
+                },
+                Match {
+                    pattern: 3,
+                    start: 4,
+                    end: 6
+                },
+                Match {
+                    pattern: 2,
+                    start: 5,
+                    end: 10
+                },
+            ]
+        );
+        assert_eq!(matcher.find_overlapping("").count(), 0);
+        assert_eq!(matcher.find_overlapping("xyz").count(), 0);
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_suffixes() {
+        // "he" and "she" end at the same position, "hers" needs the fail link from "she"
+        let matcher = MultiPatternMatcher::new(&["he", "she", "his", "hers"]);
+        let matches = matcher
+            .find_overlapping("ushers")
+            .map(|m| (m.pattern, m.start))
+            .collect_vec();
+        assert_eq!(matches, vec![(1, 1), (0, 2), (3, 2)]);
+    }
+
+    #[test]
+    fn test_multi_pattern_matcher_empty_patterns() {
+        let matcher = MultiPatternMatcher::new(&["", "ab", ""]);
+        let matches = matcher
+            .find_overlapping("xabab")
+            .map(|m| (m.pattern, m.start))
+            .collect_vec();
+        assert_eq!(matches, vec![(1, 1), (1, 3)]);
+        assert_eq!(
+            MultiPatternMatcher::new(&[""])
+                .find_overlapping("abc")
+                .count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_integers() -> Result<(), IntegersError> {
+        assert_eq!(integers::<u32>("move 13 from 2 to 10")?, vec![13, 2, 10]);
+        assert_eq!(integers::<u32>("2-4,6-8")?, vec![2, 4, 6, 8]);
+        assert_eq!(integers::<i64>("x=-3, y=12-5")?, vec![-3, 12, -5]);
+        assert_eq!(integers::<i64>("- 3")?, vec![3]);
+        assert!(integers::<usize>("no numbers")?.is_empty());
+        assert_eq!(
+            integers::<usize>("Card   1: 41 48 | 83  6")?,
+            vec![1, 41, 48, 83, 6]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_integers_errors() {
+        let error = integers::<u8>("1 300").unwrap_err();
+        assert_eq!(error.line_number, None);
+        assert_eq!(
+            error.to_string(),
+            "invalid integer '300' (number too large to fit in target type) in '1 300'"
+        );
+
+        let error = integers_array::<u32, 3>("move 1 from 2")
+            .unwrap_err()
+            .with_line_number(7);
+        assert_eq!(
+            error.kind,
+            IntegersErrorKind::WrongCount {
+                expected: 3,
+                found: 2
+            }
+        );
+        assert_eq!(
+            error.to_string(),
+            "Line 7: expected 3 integers, found 2 in 'move 1 from 2'"
+        );
+    }
+
+    #[test]
+    fn test_integers_array_and_per_line() -> Result<(), IntegersError> {
+        let [destination, source, size] = integers_array::<usize, 3>("50 98 2")?;
+        assert_eq!((destination, source, size), (50, 98, 2));
+
+        assert_eq!(
+            integers_per_line::<i32>("1 2\n\n-3")?,
+            vec![vec![1, 2], vec![], vec![-3]]
+        );
+        assert_eq!(
+            integers_per_line::<u8>("1\n2\n256")
+                .unwrap_err()
+                .line_number,
+            Some(3)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_skip_empty_lines() {
+        let lines = vec!["", "  ", "a", "", "b", " ", ""];
+        assert_eq!(lines.skip_empty_start_lines(), vec!["a", "", "b", " ", ""]);
+        assert_eq!(lines.skip_empty_end_lines(), vec!["", "  ", "a", "", "b"]);
+        assert_eq!(lines.trim_empty_lines(), vec!["a", "", "b"]);
+        assert_eq!(vec!["", " "].trim_empty_lines(), Vec::<&str>::new());
+
+        let string = String::from("\n \na\n\nb\n\n");
+        assert_eq!(string.skip_empty_start_lines(), "a\n\nb\n");
+        assert_eq!(string.trim_empty_lines(), "a\n\nb");
+    }
+
+    #[test]
+    fn test_dedent() {
+        assert_eq!(
+            dedent(
+                "
+                    [D]
+                [N] [C]
+                 1   2
+                "
+            ),
+            "    [D]\n[N] [C]\n 1   2"
+        );
+        assert_eq!(dedent("  a\n    \n    b"), "a\n\n  b");
+        assert_eq!(dedent("\u{3000}a\n  b"), "a\n b");
+        assert_eq!(dedent("\u{3000}\u{3000}a\n\u{3000}b"), "\u{3000}a\nb");
+        assert_eq!(dedent(""), "");
+    }
+
+    #[test]
+    fn test_sections() {
+        let input = "\nseeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\n\n   \nlast\n";
+        let sections = sections(input).collect_vec();
+        assert_eq!(sections.len(), 3);
+        assert_eq!(sections[0].start, 1);
+        assert_eq!(sections[0].lines(), &["seeds: 79 14"]);
+        assert_eq!(sections[1].start, 3);
+        assert_eq!(sections[1].header(), "seed-to-soil map:");
+        assert_eq!(sections[1].body(), &["50 98 2", "52 50 48"]);
+        assert_eq!(sections[2].start, 9);
+        assert_eq!(sections[2].text(), "last");
+
+        assert_eq!(super::sections("\n  \n").count(), 0);
+    }
 }
@@ -0,0 +1,411 @@
+use crate::client::Client;
+use anyhow::{anyhow, bail, ensure, Context, Result};
+use regex::Regex;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::{self, Write};
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What the server said about a submitted answer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without a hint about the direction.
+    Wrong,
+    /// Submitted too soon after the previous attempt; nothing was checked.
+    Wait(u64),
+    /// The part was already solved, or part 2 isn't unlocked yet.
+    WrongLevel,
+}
+
+impl Outcome {
+    /// Reads the outcome from the page returned after submitting an answer.
+    pub fn parse(page: &str) -> Result<Outcome> {
+        let wait = Regex::new(r"[Yy]ou have (?:(\d+)m )?(\d+)s left to wait").unwrap();
+        if page.contains("That's the right answer") {
+            Ok(Outcome::Correct)
+        } else if page.contains("That's not the right answer") {
+            if page.contains("your answer is too high") {
+                Ok(Outcome::TooHigh)
+            } else if page.contains("your answer is too low") {
+                Ok(Outcome::TooLow)
+            } else {
+                Ok(Outcome::Wrong)
+            }
+        } else if let Some(captures) = wait.captures(page) {
+            let minutes = captures
+                .get(1)
+                .map_or(Ok(0), |m| m.as_str().parse::<u64>())?;
+            let seconds = captures[2].parse::<u64>()?;
+            Ok(Outcome::Wait(minutes * 60 + seconds))
+        } else if page.contains("You don't seem to be solving the right level") {
+            Ok(Outcome::WrongLevel)
+        } else {
+            bail!("Unexpected answer page: {page}")
+        }
+    }
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "too-high"),
+            Outcome::TooLow => write!(f, "too-low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::Wait(seconds) => write!(f, "wait-{seconds}"),
+            Outcome::WrongLevel => write!(f, "wrong-level"),
+        }
+    }
+}
+
+impl FromStr for Outcome {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "correct" => Ok(Outcome::Correct),
+            "too-high" => Ok(Outcome::TooHigh),
+            "too-low" => Ok(Outcome::TooLow),
+            "wrong" => Ok(Outcome::Wrong),
+            "wrong-level" => Ok(Outcome::WrongLevel),
+            _ => {
+                let seconds = s
+                    .strip_prefix("wait-")
+                    .ok_or_else(|| anyhow!("Unknown outcome '{s}'"))?;
+                Ok(Outcome::Wait(seconds.parse()?))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Attempt {
+    pub year: u32,
+    pub day: u32,
+    pub part: u8,
+    /// Seconds since the Unix epoch.
+    pub time: u64,
+    pub outcome: Outcome,
+    pub answer: String,
+}
+
+impl Attempt {
+    fn is_for(&self, year: u32, day: u32, part: u8) -> bool {
+        self.year == year && self.day == day && self.part == part
+    }
+}
+
+impl Display for Attempt {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}\t{}\t{}\t{}\t{}\t{}",
+            self.year, self.day, self.part, self.time, self.outcome, self.answer
+        )
+    }
+}
+
+impl FromStr for Attempt {
+    type Err = anyhow::Error;
+
+    fn from_str(line: &str) -> Result<Self> {
+        let fields: [&str; 6] = line
+            .splitn(6, '\t')
+            .collect::<Vec<_>>()
+            .try_into()
+            .map_err(|fields: Vec<&str>| anyhow!("Expected 6 fields, found {}", fields.len()))?;
+        Ok(Attempt {
+            year: fields[0].parse()?,
+            day: fields[1].parse()?,
+            part: fields[2].parse()?,
+            time: fields[3].parse()?,
+            outcome: fields[4].parse()?,
+            answer: fields[5].to_string(),
+        })
+    }
+}
+
+/// Every answer ever submitted, one tab separated line per attempt, so that answers known to be
+/// wrong are never sent again.
+#[derive(Debug)]
+pub struct Ledger {
+    path: PathBuf,
+    attempts: Vec<Attempt>,
+}
+
+impl Ledger {
+    /// Reads the attempts recorded in `path`, none when it doesn't exist yet. Any other error
+    /// is returned, so that answers known to be wrong can't be submitted again.
+    pub fn load(path: PathBuf) -> Result<Ledger> {
+        let attempts = match fs::read_to_string(&path) {
+            Ok(contents) => contents
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .map(|(i, line)| {
+                    line.parse::<Attempt>()
+                        .with_context(|| format!("{}:{}: invalid attempt", path.display(), i + 1))
+                })
+                .collect::<Result<Vec<_>>>()?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => vec![],
+            Err(err) => {
+                return Err(err).with_context(|| format!("Can't read {}", path.display()));
+            }
+        };
+        Ok(Ledger { path, attempts })
+    }
+
+    pub fn record(&mut self, attempt: Attempt) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .with_context(|| format!("Can't open {}", self.path.display()))?;
+        writeln!(file, "{attempt}")?;
+        self.attempts.push(attempt);
+        Ok(())
+    }
+
+    /// Fails when the attempts so far already tell `answer` is wrong, the part is solved, or the
+    /// server asked to wait until after `now`.
+    pub fn check(&self, year: u32, day: u32, part: u8, answer: &str, now: u64) -> Result<()> {
+        let number = answer.parse::<i128>().ok();
+        let attempts = self.attempts.iter();
+        for attempt in attempts.filter(|attempt| attempt.is_for(year, day, part)) {
+            let known = || attempt.answer.parse::<i128>().ok();
+            match attempt.outcome {
+                Outcome::Correct => bail!("Already solved with {}", attempt.answer),
+                Outcome::Wait(seconds) if attempt.time + seconds > now => bail!(
+                    "Submitted too recently, wait {}s more",
+                    attempt.time + seconds - now
+                ),
+                Outcome::Wait(_) | Outcome::WrongLevel => (),
+                _ if attempt.answer == answer => {
+                    bail!("{answer} was already submitted and is {}", attempt.outcome)
+                }
+                Outcome::TooHigh => {
+                    if let (Some(number), Some(known)) = (number, known()) {
+                        ensure!(
+                            number < known,
+                            "{answer} is not below {known}, known to be too high"
+                        );
+                    }
+                }
+                Outcome::TooLow => {
+                    if let (Some(number), Some(known)) = (number, known()) {
+                        ensure!(
+                            number > known,
+                            "{answer} is not above {known}, known to be too low"
+                        );
+                    }
+                }
+                Outcome::Wrong => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_secs())
+}
+
+/// Submits `answer` for a part of a day unless the ledger says it can't be right, and records
+/// the outcome.
+pub fn submit(
+    client: &Client,
+    ledger: &mut Ledger,
+    year: u32,
+    day: u32,
+    part: u8,
+    answer: &str,
+) -> Result<Outcome> {
+    ensure!(part == 1 || part == 2, "Part must be 1 or 2, got {part}");
+    ensure!(
+        !answer.is_empty() && !answer.contains(char::is_whitespace),
+        "Answer must be a single word, got '{answer}'"
+    );
+    let time = now();
+    ledger.check(year, day, part, answer, time)?;
+
+    let page = client.post_form(
+        &format!("/{year}/day/{day}/answer"),
+        &[("level", &part.to_string()), ("answer", answer)],
+    )?;
+    let outcome = Outcome::parse(&page)?;
+    ledger.record(Attempt {
+        year,
+        day,
+        part,
+        time,
+        outcome,
+        answer: answer.to_string(),
+    })?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{RateLimiter, STATE_DIR};
+    use crate::stub_server::StubServer;
+    use std::path::Path;
+    use std::time::Duration;
+
+    const TOO_HIGH: &str = "<article><p>That's not the right answer; your answer is too high. \
+        If you're stuck, make sure you're using the full input data.</p></article>";
+    const TOO_LOW: &str = "<article><p>That's not the right answer; your answer is too low.</p>";
+    const CORRECT: &str = "<article><p>That's the right answer! You are one gold star closer.";
+    const WAIT: &str = "<article><p>You gave an answer too recently; you have to wait after \
+        submitting an answer before trying again.  You have 1m 5s left to wait.</p></article>";
+
+    fn temp_root(name: &str) -> PathBuf {
+        let root = std::env::temp_dir().join(format!("submit-{}-{name}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        root
+    }
+
+    fn client(server: &StubServer, root: &Path) -> Client {
+        let rate_limiter = RateLimiter::new(Duration::ZERO, root.join(STATE_DIR).join("last"));
+        Client::new(&server.url, "abc123", rate_limiter)
+    }
+
+    fn attempt(part: u8, time: u64, outcome: Outcome, answer: &str) -> Attempt {
+        Attempt {
+            year: 2023,
+            day: 4,
+            part,
+            time,
+            outcome,
+            answer: answer.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_parse_outcome() -> Result<()> {
+        assert_eq!(Outcome::parse(TOO_HIGH)?, Outcome::TooHigh);
+        assert_eq!(Outcome::parse(TOO_LOW)?, Outcome::TooLow);
+        assert_eq!(Outcome::parse(CORRECT)?, Outcome::Correct);
+        assert_eq!(Outcome::parse(WAIT)?, Outcome::Wait(65));
+        assert_eq!(
+            Outcome::parse("You have 7s left to wait.")?,
+            Outcome::Wait(7)
+        );
+        assert_eq!(
+            Outcome::parse("<p>That's not the right answer.</p>")?,
+            Outcome::Wrong
+        );
+        assert!(Outcome::parse("<html>Login</html>").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_attempt_round_trip() -> Result<()> {
+        for outcome in [Outcome::Wait(30), Outcome::TooLow, Outcome::WrongLevel] {
+            let attempt = attempt(2, 1700000000, outcome, "CMZ");
+            assert_eq!(attempt.to_string().parse::<Attempt>()?, attempt);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_check() {
+        let ledger = Ledger {
+            path: PathBuf::new(),
+            attempts: vec![
+                attempt(1, 100, Outcome::TooHigh, "500"),
+                attempt(1, 200, Outcome::TooLow, "100"),
+                attempt(1, 300, Outcome::Wrong, "250"),
+                attempt(1, 400, Outcome::Wait(60), "260"),
+                attempt(2, 100, Outcome::Correct, "42"),
+            ],
+        };
+        assert!(ledger.check(2023, 4, 1, "300", 500).is_ok());
+        let error = |answer: &str, now: u64| {
+            ledger
+                .check(2023, 4, 1, answer, now)
+                .unwrap_err()
+                .to_string()
+        };
+        assert_eq!(error("250", 500), "250 was already submitted and is wrong");
+        assert_eq!(
+            error("500", 500),
+            "500 was already submitted and is too-high"
+        );
+        assert_eq!(
+            error("600", 500),
+            "600 is not below 500, known to be too high"
+        );
+        assert_eq!(error("99", 500), "99 is not above 100, known to be too low");
+        assert_eq!(error("300", 430), "Submitted too recently, wait 30s more");
+        assert_eq!(
+            ledger.check(2023, 4, 2, "43", 500).unwrap_err().to_string(),
+            "Already solved with 42"
+        );
+        assert!(ledger.check(2023, 5, 1, "600", 500).is_ok());
+    }
+
+    #[test]
+    fn test_submit() -> Result<()> {
+        let root = temp_root("submit");
+        let server = StubServer::start(|request| match request.body.as_str() {
+            "level=1&answer=500" => (200, TOO_HIGH.to_string()),
+            _ => (200, CORRECT.to_string()),
+        });
+        let client = client(&server, &root);
+        let ledger_path = root.join("ledger.tsv");
+        let mut ledger = Ledger::load(ledger_path.clone())?;
+
+        assert_eq!(
+            submit(&client, &mut ledger, 2023, 4, 1, "500")?,
+            Outcome::TooHigh
+        );
+        let error = submit(&client, &mut ledger, 2023, 4, 1, "700").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "700 is not below 500, known to be too high"
+        );
+        assert_eq!(
+            submit(&client, &mut ledger, 2023, 4, 1, "400")?,
+            Outcome::Correct
+        );
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[0].method, "POST");
+        assert_eq!(requests[0].path, "/2023/day/4/answer");
+        assert_eq!(requests[1].body, "level=1&answer=400");
+
+        let reloaded = Ledger::load(ledger_path)?;
+        assert_eq!(reloaded.attempts, ledger.attempts);
+        assert_eq!(reloaded.attempts.len(), 2);
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_load_unreadable_ledger() -> Result<()> {
+        let root = temp_root("ledger");
+        fs::create_dir_all(&root)?;
+        assert!(Ledger::load(root.join("missing.tsv"))?.attempts.is_empty());
+
+        let error = Ledger::load(root.clone()).unwrap_err();
+        assert_eq!(error.to_string(), format!("Can't read {}", root.display()));
+        let path = root.join("ledger.tsv");
+        fs::write(&path, [0xff, 0xfe])?;
+        assert!(Ledger::load(path).is_err());
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use anyhow::{ensure, Context, Result};
+use itertools::{EitherOrBoth, Itertools};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Time left for an editor to finish saving before a change is acted on.
+const SETTLE_DELAY: Duration = Duration::from_millis(200);
+
+/// Last modification time of every watched file, `None` for files that don't exist (yet).
+type Snapshot = BTreeMap<PathBuf, Option<SystemTime>>;
+
+/// The files a day depends on: its module, its grammar, its input and its examples.
+fn watched_paths(root: &Path, year: u32, day: u32) -> Vec<PathBuf> {
+    let solutions_dir = root.join(format!("src/solutions{year}"));
+    let inputs_dir = root.join(format!("inputs/{year}"));
+    let mut paths = vec![
+        solutions_dir.join(format!("day{day}.rs")),
+        solutions_dir.join(format!("day{day}.pest")),
+        inputs_dir.join(format!("day{day}.txt")),
+    ];
+    let example_prefix = format!("day{day}.example");
+    if let Ok(entries) = fs::read_dir(&inputs_dir) {
+        paths.extend(
+            entries
+                .flatten()
+                .filter(|entry| {
+                    entry
+                        .file_name()
+                        .to_string_lossy()
+                        .starts_with(&example_prefix)
+                })
+                .map(|entry| entry.path())
+                .sorted(),
+        );
+    }
+    paths
+}
+
+fn snapshot(paths: &[PathBuf]) -> Snapshot {
+    paths
+        .iter()
+        .map(|path| {
+            let modified = fs::metadata(path).and_then(|metadata| metadata.modified());
+            (path.clone(), modified.ok())
+        })
+        .collect()
+}
+
+/// Files created, modified or deleted between two snapshots.
+fn changed(before: &Snapshot, after: &Snapshot) -> Vec<PathBuf> {
+    before
+        .keys()
+        .chain(after.keys())
+        .unique()
+        .filter(|path| {
+            let modified = |snapshot: &Snapshot| snapshot.get(*path).copied().flatten();
+            modified(before) != modified(after)
+        })
+        .cloned()
+        .collect_vec()
+}
+
+/// Line by line comparison of two runs: unchanged lines are indented, the others are shown as
+/// removed with `-` and added with `+`.
+fn diff_lines(previous: &[String], current: &[String]) -> Vec<String> {
+    previous
+        .iter()
+        .zip_longest(current)
+        .flat_map(|lines| match lines {
+            EitherOrBoth::Both(old, new) if old == new => vec![format!("  {new}")],
+            EitherOrBoth::Both(old, new) => vec![format!("- {old}"), format!("+ {new}")],
+            EitherOrBoth::Left(old) => vec![format!("- {old}")],
+            EitherOrBoth::Right(new) => vec![format!("+ {new}")],
+        })
+        .collect_vec()
+}
+
+/// Cargo arguments enabling the features of the running binary, and only those.
+fn feature_args() -> Vec<String> {
+    let features = [
+        ("year2022", cfg!(feature = "year2022")),
+        ("year2023", cfg!(feature = "year2023")),
+        ("grammar", cfg!(feature = "grammar")),
+    ];
+    let enabled = features
+        .iter()
+        .filter(|(_, enabled)| *enabled)
+        .map(|(feature, _)| *feature)
+        .join(",");
+    let mut args = vec!["--no-default-features".to_string()];
+    if !enabled.is_empty() {
+        args.extend(["--features".to_string(), enabled]);
+    }
+    args
+}
+
+fn cargo(args: &[&str]) -> Command {
+    let mut command = Command::new(env::var("CARGO").unwrap_or("cargo".to_string()));
+    command.args(args).args(feature_args());
+    // Rebuild with the profile and the features of the running binary, so that it is the one
+    // replaced
+    if !cfg!(debug_assertions) {
+        command.arg("--release");
+    }
+    command
+}
+
+/// Rebuilds, runs the tests of the day, which check its examples, and solves the real input.
+/// Returns the lines printed for the real input, or `None` when the build failed.
+/// `binary` is the path of the executable the build replaces.
+fn run_once(binary: &Path, year: u32, day: u32) -> Result<Option<Vec<String>>> {
+    let build = cargo(&["build", "--quiet"]).status()?;
+    if !build.success() {
+        return Ok(None);
+    }
+
+    let filter = format!("solutions{year}::day{day}::");
+    let tests = cargo(&["test", "--quiet"]).arg(&filter).status()?;
+    if !tests.success() {
+        println!("Tests of {year} day {day} failed");
+    }
+
+    let output = Command::new(binary)
+        .args([
+            "run",
+            "--year",
+            &year.to_string(),
+            "--day",
+            &day.to_string(),
+        ])
+        .output()
+        .context("Can't run the rebuilt binary")?;
+    let mut lines = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .chain(String::from_utf8_lossy(&output.stderr).lines())
+        .map(String::from)
+        .collect_vec();
+    if !output.status.success() {
+        lines.push(format!(
+            "Solving {year} day {day} failed: {}",
+            output.status
+        ));
+    }
+    Ok(Some(lines))
+}
+
+/// Re-runs a day every time one of its files changes, until interrupted. Must be started from
+/// the repository root, as it rebuilds the project with cargo.
+pub fn watch(year: u32, day: u32) -> Result<()> {
+    let root = Path::new(".");
+    ensure!(
+        root.join("Cargo.toml").exists(),
+        "--watch must be run from the repository root"
+    );
+    // Once rebuilt, the running executable is deleted, and on Linux its path gets a " (deleted)"
+    // suffix: the path of the rebuilt one must be known before
+    let binary = env::current_exe().context("Can't find the running binary")?;
+    let mut previous: Option<Vec<String>> = None;
+    let mut last = snapshot(&watched_paths(root, year, day));
+    let mut changes: Vec<PathBuf> = vec![];
+    loop {
+        println!("======== {year} day {day} ========");
+        for path in &changes {
+            println!("Changed: {}", path.display());
+        }
+        // Failures are reported, the next change may fix them
+        match run_once(&binary, year, day) {
+            Ok(Some(lines)) => {
+                let diff = match &previous {
+                    Some(previous) => diff_lines(previous, &lines),
+                    None => lines.iter().map(|line| format!("  {line}")).collect_vec(),
+                };
+                diff.iter().for_each(|line| println!("{line}"));
+                previous = Some(lines);
+            }
+            Ok(None) => println!("Build failed"),
+            Err(err) => println!("{err:#}"),
+        }
+        println!("Watching for changes...");
+
+        changes = loop {
+            thread::sleep(POLL_INTERVAL);
+            let current = snapshot(&watched_paths(root, year, day));
+            let changes = changed(&last, &current);
+            if !changes.is_empty() {
+                thread::sleep(SETTLE_DELAY);
+                last = snapshot(&watched_paths(root, year, day));
+                break changes;
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect_vec()
+    }
+
+    #[test]
+    fn test_watched_paths() -> Result<()> {
+        let root = env::temp_dir().join(format!("watch-{}", std::process::id()));
+        fs::create_dir_all(root.join("inputs/2023"))?;
+        for file in [
+            "day3.example.txt",
+            "day3.example2.txt",
+            "day30.txt",
+            "day4.example.txt",
+        ] {
+            fs::write(root.join("inputs/2023").join(file), "")?;
+        }
+
+        let paths = watched_paths(&root, 2023, 3);
+        let names = paths
+            .iter()
+            .map(|path| path.strip_prefix(&root).unwrap().display().to_string())
+            .collect_vec();
+        assert_eq!(
+            names,
+            [
+                "src/solutions2023/day3.rs",
+                "src/solutions2023/day3.pest",
+                "inputs/2023/day3.txt",
+                "inputs/2023/day3.example.txt",
+                "inputs/2023/day3.example2.txt",
+            ]
+        );
+
+        let before = snapshot(&paths);
+        fs::write(root.join("inputs/2023/day3.txt"), "1 2 3")?;
+        let after = snapshot(&paths);
+        assert_eq!(
+            changed(&before, &after),
+            [root.join("inputs/2023/day3.txt")]
+        );
+        assert!(changed(&after, &snapshot(&paths)).is_empty());
+        fs::remove_dir_all(&root)?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_feature_args() {
+        let args = feature_args();
+        assert_eq!(args[0], "--no-default-features");
+        let features = args.get(2).map(String::as_str).unwrap_or_default();
+        assert_eq!(
+            features.split(',').contains(&"year2022"),
+            cfg!(feature = "year2022")
+        );
+        assert_eq!(
+            features.split(',').contains(&"grammar"),
+            cfg!(feature = "grammar")
+        );
+    }
+
+    #[test]
+    fn test_diff_lines() {
+        let previous = lines(&["********", "Day3: (4361, 0)"]);
+        let current = lines(&["********", "Day3: (4361, 467835)", "error"]);
+        assert_eq!(
+            diff_lines(&previous, &current),
+            [
+                "  ********",
+                "- Day3: (4361, 0)",
+                "+ Day3: (4361, 467835)",
+                "+ error"
+            ]
+        );
+        assert_eq!(diff_lines(&current[..1], &previous[..0]), ["- ********"]);
+    }
+}